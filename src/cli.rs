@@ -0,0 +1,181 @@
+//! Command-line arguments. They are all validated before the terminal is
+//! touched, and override the config file where both set something.
+use crate::{events::Severity, export, theme::Theme};
+use std::{ffi::OsString, path::PathBuf, time::Duration};
+
+pub const USAGE: &str = "\
+Usage: cotermi [OPTIONS]
+       cotermi export [--format jsonl|csv] [--output FILE] [--min-level LEVEL]
+                      [--config FILE] [FILE...]
+
+Options:
+  --tick-rate MS      how often sources are polled, in milliseconds (or 2s)
+  --config FILE       the config file to read instead of the default one
+  --data-file FILE    where items are saved instead of the default file
+  --theme NAME        a built-in theme: dark, light or high-contrast
+  --tail FILE         follow a log file; may be repeated
+  --run COMMAND       run a shell command and stream its output; may be repeated
+  --version           print the version and exit
+  --help              print this help and exit
+
+The export subcommand parses log files, or stdin, like tailed lines and writes
+them out as events, to FILE or stdout. Lines are parsed as set in the config
+file, or in the one --config names.";
+
+/// What cotermi was asked to do.
+#[derive(Debug)]
+pub enum Invocation {
+    Run(Args),
+    Export(ExportArgs),
+    Help,
+    Version,
+}
+
+/// Options for the interactive app; unset ones come from the config file.
+#[derive(Debug, Default)]
+pub struct Args {
+    pub tick_rate: Option<Duration>,
+    pub config: Option<PathBuf>,
+    pub data_file: Option<PathBuf>,
+    /// Already checked to name a built-in theme.
+    pub theme: Option<String>,
+    pub tails: Vec<PathBuf>,
+    pub commands: Vec<String>,
+}
+
+/// Options for `cotermi export`.
+#[derive(Debug, Default)]
+pub struct ExportArgs {
+    /// Inferred from the output file if unset, else JSON Lines.
+    pub format: Option<export::Format>,
+    pub output: Option<PathBuf>,
+    pub min_level: Option<Severity>,
+    pub config: Option<PathBuf>,
+    /// `-` stands for stdin, which is also read if there are none.
+    pub inputs: Vec<PathBuf>,
+}
+
+/// Parses a tick rate such as `250`, `250ms` or `2s`.
+pub fn parse_duration(text: &str) -> Option<Duration> {
+    let (number, scale) = match text.strip_suffix("ms") {
+        Some(number) => (number, 1),
+        None => match text.strip_suffix('s') {
+            Some(number) => (number, 1_000),
+            None => (text, 1),
+        },
+    };
+    let millis = number.trim().parse::<u64>().ok()?.checked_mul(scale)?;
+    Some(Duration::from_millis(millis)).filter(|duration| !duration.is_zero())
+}
+
+/// The arguments after the program name, split into an option and its
+/// value, taken either from `--option=value` or from the next argument.
+struct Parser<I> {
+    args: I,
+}
+
+impl<I: Iterator<Item = OsString>> Parser<I> {
+    fn next(&mut self) -> Option<(OsString, Option<OsString>)> {
+        let arg = self.args.next()?;
+        match arg.to_str().and_then(|arg| arg.split_once('=')) {
+            Some((option, value)) if option.starts_with("--") => {
+                Some((option.into(), Some(value.into())))
+            }
+            _ => Some((arg, None)),
+        }
+    }
+    fn value(&mut self, option: &OsString, inline: Option<OsString>) -> Result<OsString, String> {
+        inline
+            .or_else(|| self.args.next())
+            .ok_or_else(|| format!("{} needs a value", option.to_string_lossy()))
+    }
+    fn text(&mut self, option: &OsString, inline: Option<OsString>) -> Result<String, String> {
+        self.value(option, inline)?.into_string().map_err(|value| {
+            format!(
+                "{}: {:?} is not valid UTF-8",
+                option.to_string_lossy(),
+                value
+            )
+        })
+    }
+}
+
+/// Parses the arguments after the program name.
+pub fn parse(args: impl IntoIterator<Item = OsString>) -> Result<Invocation, String> {
+    let mut args = args.into_iter().peekable();
+    if args.peek().is_some_and(|arg| arg == "export") {
+        args.next();
+        return parse_export(Parser { args }).map(Invocation::Export);
+    }
+    let mut parser = Parser { args };
+    let mut parsed = Args::default();
+    while let Some((option, inline)) = parser.next() {
+        match option.to_str() {
+            Some("--help" | "-h") => return Ok(Invocation::Help),
+            Some("--version" | "-V") => return Ok(Invocation::Version),
+            Some("--tick-rate") => {
+                let text = parser.text(&option, inline)?;
+                match parse_duration(&text) {
+                    Some(rate) => parsed.tick_rate = Some(rate),
+                    None => {
+                        return Err(format!(
+                            "--tick-rate: {:?} is not a positive number of milliseconds",
+                            text
+                        ))
+                    }
+                }
+            }
+            Some("--config") => parsed.config = Some(parser.value(&option, inline)?.into()),
+            Some("--data-file") => parsed.data_file = Some(parser.value(&option, inline)?.into()),
+            Some("--theme") => {
+                let name = parser.text(&option, inline)?;
+                if Theme::named(&name).is_none() {
+                    return Err(format!(
+                        "--theme: unknown theme {:?}, expected one of {}",
+                        name,
+                        Theme::NAMES.join(", ")
+                    ));
+                }
+                parsed.theme = Some(name);
+            }
+            Some("--tail") => parsed.tails.push(parser.value(&option, inline)?.into()),
+            Some("--run") => parsed.commands.push(parser.text(&option, inline)?),
+            _ => return Err(format!("unexpected argument {:?}", option)),
+        }
+    }
+    Ok(Invocation::Run(parsed))
+}
+
+fn parse_export(mut parser: Parser<impl Iterator<Item = OsString>>) -> Result<ExportArgs, String> {
+    let mut parsed = ExportArgs::default();
+    while let Some((option, inline)) = parser.next() {
+        match option.to_str() {
+            Some("--format") => {
+                let name = parser.text(&option, inline)?;
+                match export::Format::from_name(&name) {
+                    Some(format) => parsed.format = Some(format),
+                    None => {
+                        return Err(format!(
+                            "--format: unknown format {:?}, expected jsonl or csv",
+                            name
+                        ))
+                    }
+                }
+            }
+            Some("--output") => parsed.output = Some(parser.value(&option, inline)?.into()),
+            Some("--config") => parsed.config = Some(parser.value(&option, inline)?.into()),
+            Some("--min-level") => {
+                let name = parser.text(&option, inline)?;
+                match Severity::from_name(&name) {
+                    Some(level) => parsed.min_level = Some(level),
+                    None => return Err(format!("--min-level: unknown severity {:?}", name)),
+                }
+            }
+            Some(arg) if arg.starts_with("--") => {
+                return Err(format!("unexpected argument {:?}", option))
+            }
+            _ => parsed.inputs.push(option.into()),
+        }
+    }
+    Ok(parsed)
+}
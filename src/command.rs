@@ -0,0 +1,230 @@
+//! Runs a shell command and collects its output line by line, so that it
+//! can be streamed into the event log.
+use std::{
+    io::{self, BufRead, BufReader, Read},
+    process::{Child, ExitStatus, Stdio},
+    sync::mpsc::{self, Receiver, Sender, TryRecvError},
+    thread::{self, JoinHandle},
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Stream {
+    Stdout,
+    Stderr,
+}
+
+/// What happened to a command since it was last polled.
+#[derive(Debug)]
+pub enum Output {
+    Line(Stream, String),
+    /// The command ended. Processes it left running in the background may
+    /// still write lines afterwards.
+    Exited(ExitStatus),
+}
+
+/// A line read from one of the child's pipes, or `None` once it closed.
+type Message = (Stream, Option<String>);
+
+pub struct Command {
+    line: String,
+    child: Option<Child>,
+    receiver: Option<Receiver<Message>>,
+    open_streams: usize,
+    status: Option<ExitStatus>,
+    /// The threads reading the pipes of the last run, joined before the next
+    /// run or when dropped.
+    readers: Vec<JoinHandle<()>>,
+    /// The process group of the last run, which outlives its exit while
+    /// background processes it started hold the pipes open.
+    group: Option<u32>,
+}
+
+impl Command {
+    /// A command run through the shell; it is not started yet.
+    pub fn new(line: impl Into<String>) -> Self {
+        Self {
+            line: line.into(),
+            child: None,
+            receiver: None,
+            open_streams: 0,
+            status: None,
+            readers: vec![],
+            group: None,
+        }
+    }
+    pub fn line(&self) -> &str {
+        &self.line
+    }
+    /// The program name, to tell the output of several commands apart.
+    pub fn name(&self) -> &str {
+        self.line.split_whitespace().next().unwrap_or(&self.line)
+    }
+    /// Whether the command was started and its exit was not reported yet.
+    pub fn is_running(&self) -> bool {
+        self.child.is_some()
+    }
+    fn shell(&self) -> std::process::Command {
+        let mut command = match cfg!(windows) {
+            true => std::process::Command::new("cmd"),
+            false => std::process::Command::new("sh"),
+        };
+        command.arg(if cfg!(windows) { "/C" } else { "-c" });
+        command.arg(&self.line);
+        // A group of its own, so that killing it also kills what it started
+        #[cfg(unix)]
+        std::os::unix::process::CommandExt::process_group(&mut command, 0);
+        command
+    }
+    /// Starts the command; does nothing if it is already running.
+    pub fn start(&mut self) -> io::Result<()> {
+        if self.is_running() {
+            return Ok(());
+        }
+        let mut child = self
+            .shell()
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        let (sender, receiver) = mpsc::channel();
+        if let Some(stdout) = child.stdout.take() {
+            self.readers
+                .push(read_lines(stdout, Stream::Stdout, sender.clone()));
+        }
+        if let Some(stderr) = child.stderr.take() {
+            self.readers
+                .push(read_lines(stderr, Stream::Stderr, sender));
+        }
+        self.group = Some(child.id());
+        self.child = Some(child);
+        self.receiver = Some(receiver);
+        self.open_streams = 2;
+        self.status = None;
+        Ok(())
+    }
+    /// Kills the command; its exit is reported by the next polls.
+    pub fn kill(&mut self) -> io::Result<()> {
+        match &mut self.child {
+            Some(child) if self.status.is_none() => kill(child),
+            _ => Ok(()),
+        }
+    }
+    /// Kills the command if it is running and starts it again. Output of the
+    /// previous run that was not polled yet is dropped.
+    pub fn restart(&mut self) -> io::Result<()> {
+        self.stop();
+        self.start()
+    }
+    /// Kills what is left of the last run and joins its readers.
+    fn stop(&mut self) {
+        if let Some(mut child) = self.child.take() {
+            if self.status.is_none() {
+                let _ = kill(&mut child);
+                let _ = child.wait();
+            }
+        }
+        // Background processes of a run that exited may still hold the
+        // pipes; a reader only finishes once they are gone
+        #[cfg(unix)]
+        if let Some(group) = self.group.take() {
+            if self.readers.iter().any(|reader| !reader.is_finished()) {
+                let _ = kill_group(group);
+            }
+        }
+        self.receiver = None;
+        for reader in self.readers.drain(..) {
+            let _ = reader.join();
+        }
+    }
+    /// The lines written since the last poll, followed by the exit status once
+    /// the command ended.
+    pub fn poll(&mut self) -> Vec<Output> {
+        let mut outputs = vec![];
+        if let Some(receiver) = &self.receiver {
+            loop {
+                match receiver.try_recv() {
+                    Ok((stream, Some(line))) => outputs.push(Output::Line(stream, line)),
+                    Ok((_, None)) => self.open_streams -= 1,
+                    Err(TryRecvError::Empty) => break,
+                    Err(TryRecvError::Disconnected) => {
+                        self.open_streams = 0;
+                        break;
+                    }
+                }
+            }
+        }
+        // Output still in flight gets one more poll to arrive before the exit
+        // is reported, but background processes keeping the pipes open must
+        // not hold it back; their output keeps streaming afterwards.
+        let waited = self.status.is_some();
+        if let (Some(child), None) = (&mut self.child, self.status) {
+            self.status = child.try_wait().unwrap_or_default();
+        }
+        if let Some(status) = self.status.filter(|_| waited || self.open_streams == 0) {
+            self.child = None;
+            self.status = None;
+            outputs.push(Output::Exited(status));
+        }
+        outputs
+    }
+}
+
+impl Drop for Command {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Kills the child's whole process group, or just the child elsewhere.
+fn kill(child: &mut Child) -> io::Result<()> {
+    #[cfg(unix)]
+    return kill_group(child.id());
+    #[cfg(not(unix))]
+    match child.kill() {
+        Err(err) if err.kind() != io::ErrorKind::InvalidInput => Err(err),
+        _ => Ok(()),
+    }
+}
+
+/// Kills the process group led by the process `group`.
+#[cfg(unix)]
+fn kill_group(group: u32) -> io::Result<()> {
+    // SAFETY: kill(2) only takes plain integers
+    let result = unsafe { libc::kill(-(group as libc::pid_t), libc::SIGKILL) };
+    match result {
+        0 => Ok(()),
+        _ => match io::Error::last_os_error() {
+            // It may have exited on its own in the meantime
+            err if err.raw_os_error() == Some(libc::ESRCH) => Ok(()),
+            err => Err(err),
+        },
+    }
+}
+
+/// Forwards every line of `pipe` from a thread of its own, then `None` once
+/// the pipe is closed.
+fn read_lines(
+    pipe: impl Read + Send + 'static,
+    stream: Stream,
+    sender: Sender<Message>,
+) -> JoinHandle<()> {
+    thread::spawn(move || {
+        let mut reader = BufReader::new(pipe);
+        let mut line = vec![];
+        loop {
+            line.clear();
+            match reader.read_until(b'\n', &mut line) {
+                Ok(0) | Err(_) => break,
+                Ok(_) => {
+                    let text = line.strip_suffix(b"\n").unwrap_or(&line);
+                    let text = text.strip_suffix(b"\r").unwrap_or(text);
+                    let text = String::from_utf8_lossy(text).into_owned();
+                    if sender.send((stream, Some(text))).is_err() {
+                        return;
+                    }
+                }
+            }
+        }
+        let _ = sender.send((stream, None));
+    })
+}
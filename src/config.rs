@@ -0,0 +1,283 @@
+use crate::{
+    events::Severity,
+    keymap::{self, KeyChord, Keymap},
+    parser::{self, Format, Parser},
+    paths,
+    rules::{Effect, Rule, Rules},
+    theme::Theme,
+    utils::Context,
+};
+use serde::Deserialize;
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+/// The config file as written by the user, before validation.
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+struct RawConfig {
+    /// How often sources are polled, in milliseconds.
+    tick_rate: Option<u64>,
+    /// How many frames per second the UI draws at most.
+    max_fps: Option<u32>,
+    /// `[keys.<context>]` tables mapping chords to action names.
+    keys: BTreeMap<String, BTreeMap<String, String>>,
+    theme: RawTheme,
+    parser: RawParser,
+    /// `[[rules]]`, evaluated in order.
+    rules: Vec<RawRule>,
+}
+
+/// `[theme]`: a built-in theme to start from, plus style overrides by name.
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct RawTheme {
+    name: Option<String>,
+    #[serde(flatten)]
+    styles: BTreeMap<String, String>,
+}
+
+/// `[parser]`: how tailed log lines are turned into events.
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+struct RawParser {
+    format: Option<String>,
+    level_key: Option<String>,
+    message_key: Option<String>,
+    timestamp_key: Option<String>,
+    pattern: Option<String>,
+}
+
+/// One `[[rules]]` entry: conditions, then what a match does.
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RawRule {
+    pattern: Option<String>,
+    source: Option<String>,
+    level: Option<String>,
+    /// Field names mapped to regexes searched in their values.
+    fields: BTreeMap<String, String>,
+    severity: Option<String>,
+    label: Option<String>,
+    actions: Vec<String>,
+}
+
+/// User settings, layered on top of the built-in defaults.
+#[derive(Clone)]
+pub struct Config {
+    pub keymap: Keymap,
+    /// Already stripped of colors if `NO_COLOR` is set.
+    pub theme: Theme,
+    pub parser: Parser,
+    pub rules: Rules,
+    /// At least 1.
+    pub max_fps: u32,
+    /// Never zero.
+    pub tick_rate: Duration,
+}
+
+/// Plenty for a terminal, while sparing the CPU when events pour in.
+pub const DEFAULT_MAX_FPS: u32 = 30;
+pub const DEFAULT_TICK_RATE: Duration = Duration::from_millis(50);
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            max_fps: DEFAULT_MAX_FPS,
+            tick_rate: DEFAULT_TICK_RATE,
+            keymap: Keymap::default(),
+            theme: Theme::default().apply_no_color(Theme::no_color_requested()),
+            parser: Parser::default(),
+            rules: Rules::default(),
+        }
+    }
+}
+
+impl Config {
+    /// `config.toml` in the [config directory](crate::paths::config_dir).
+    pub fn default_path() -> io::Result<PathBuf> {
+        Ok(paths::config_dir()?.join("config.toml"))
+    }
+    /// Loads the config file, or the defaults if it does not exist. Every
+    /// invalid entry is reported, one per line.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(contents) => Self::parse(&contents).map_err(|errors| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("invalid config file {}:\n  {}", path.display(), errors),
+                )
+            }),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(err),
+        }
+    }
+    /// Replaces the theme, style overrides included, with a built-in one.
+    pub fn set_theme(&mut self, name: &str) -> Result<(), String> {
+        let theme = Theme::named(name).ok_or_else(|| {
+            format!(
+                "unknown theme {:?}, expected one of {}",
+                name,
+                Theme::NAMES.join(", ")
+            )
+        })?;
+        self.theme = theme.apply_no_color(Theme::no_color_requested());
+        Ok(())
+    }
+    fn parse_parser(raw: &RawParser) -> Result<Parser, String> {
+        let format = match &raw.format {
+            Some(name) => Format::from_name(name).ok_or_else(|| {
+                format!(
+                    "format: unknown format {:?}, expected one of {}",
+                    name,
+                    Format::NAMES.join(", ")
+                )
+            })?,
+            None => Format::Auto,
+        };
+        let mut keys = parser::Keys::default();
+        for (key, names) in [
+            (&raw.level_key, &mut keys.level),
+            (&raw.message_key, &mut keys.message),
+            (&raw.timestamp_key, &mut keys.timestamp),
+        ] {
+            if let Some(key) = key {
+                *names = vec![key.clone()];
+            }
+        }
+        let mut parser = Parser::new(format).with_keys(keys);
+        match &raw.pattern {
+            Some(pattern) => {
+                let pattern = regex::Regex::new(pattern)
+                    .map_err(|err| format!("pattern: invalid regex: {}", err))?;
+                parser = parser.with_pattern(pattern);
+            }
+            None if format == Format::Regex => {
+                return Err("format: \"regex\" needs a pattern".to_string())
+            }
+            None => {}
+        }
+        Ok(parser)
+    }
+    fn parse_rule(raw: &RawRule) -> Result<Rule, String> {
+        let severity = |key: &str, name: &Option<String>| match name {
+            Some(name) => Severity::from_name(name)
+                .map(Some)
+                .ok_or_else(|| format!("{}: unknown severity {:?}", key, name)),
+            None => Ok(None),
+        };
+        let pattern = match &raw.pattern {
+            Some(pattern) => Some(
+                regex::Regex::new(pattern)
+                    .map_err(|err| format!("pattern: invalid regex: {}", err))?,
+            ),
+            None => None,
+        };
+        let fields = raw
+            .fields
+            .iter()
+            .map(|(name, pattern)| match regex::Regex::new(pattern) {
+                Ok(pattern) => Ok((name.clone(), pattern)),
+                Err(err) => Err(format!("fields.{}: invalid regex: {}", name, err)),
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        if pattern.is_none() && raw.source.is_none() && raw.level.is_none() && fields.is_empty() {
+            return Err("needs a pattern, source, level or fields to match".to_string());
+        }
+        let effects = raw
+            .actions
+            .iter()
+            .map(|name| {
+                Effect::from_name(name).ok_or_else(|| {
+                    format!("actions: unknown action {:?}, expected flash or bell", name)
+                })
+            })
+            .collect::<Result<Vec<Effect>, String>>()?;
+        Ok(Rule {
+            pattern,
+            source: raw.source.clone(),
+            level: severity("level", &raw.level)?,
+            fields,
+            severity: severity("severity", &raw.severity)?,
+            label: raw.label.clone(),
+            effects,
+        })
+    }
+    /// Parses a config file's contents, collecting all errors into one message.
+    pub fn parse(contents: &str) -> Result<Self, String> {
+        Self::parse_with(contents, Theme::no_color_requested())
+    }
+    /// Like [`Config::parse`], with whether to strip the theme's colors given
+    /// rather than read from `NO_COLOR`.
+    pub fn parse_with(contents: &str, no_color: bool) -> Result<Self, String> {
+        let raw: RawConfig = toml::from_str(contents).map_err(|err| err.to_string())?;
+        let mut config = Self::default();
+        let mut errors = vec![];
+        match raw.max_fps {
+            Some(0) => errors.push("max-fps: must be at least 1".to_string()),
+            Some(fps) => config.max_fps = fps,
+            None => {}
+        }
+        match raw.tick_rate {
+            Some(0) => errors.push("tick-rate: must be at least 1 millisecond".to_string()),
+            Some(millis) => config.tick_rate = Duration::from_millis(millis),
+            None => {}
+        }
+        for (context_name, bindings) in &raw.keys {
+            let context = match Context::from_name(context_name) {
+                Some(context) => context,
+                None => {
+                    errors.push(format!("[keys.{}]: unknown context", context_name));
+                    continue;
+                }
+            };
+            for (chord, action) in bindings {
+                let binding = chord
+                    .parse::<KeyChord>()
+                    .and_then(|chord| Ok((chord, keymap::parse_action(action)?)));
+                match binding {
+                    Ok((chord, action)) => config.keymap.bind(context, chord, action),
+                    Err(err) => {
+                        errors.push(format!("[keys.{}] {:?}: {}", context_name, chord, err))
+                    }
+                }
+            }
+        }
+        let mut theme = match &raw.theme.name {
+            Some(name) => Theme::named(name).unwrap_or_else(|| {
+                errors.push(format!(
+                    "[theme] name: unknown theme {:?}, expected one of {}",
+                    name,
+                    Theme::NAMES.join(", ")
+                ));
+                Theme::default()
+            }),
+            None => Theme::default(),
+        };
+        for (name, spec) in &raw.theme.styles {
+            if let Err(err) = theme.set(name, spec) {
+                errors.push(format!("[theme] {}: {}", name, err));
+            }
+        }
+        config.theme = theme.apply_no_color(no_color);
+        match Self::parse_parser(&raw.parser) {
+            Ok(parser) => config.parser = parser,
+            Err(err) => errors.push(format!("[parser] {}", err)),
+        }
+        let mut rules = vec![];
+        for (index, rule) in raw.rules.iter().enumerate() {
+            match Self::parse_rule(rule) {
+                Ok(rule) => rules.push(rule),
+                Err(err) => errors.push(format!("[[rules]] #{}: {}", index + 1, err)),
+            }
+        }
+        config.rules = Rules::new(rules);
+        match errors.is_empty() {
+            true => Ok(config),
+            false => Err(errors.join("\n  ")),
+        }
+    }
+}
@@ -0,0 +1,157 @@
+//! The threads that feed `run_app`: one reading terminal input, one ticking,
+//! and any producers library users spawn. They all send [`Message`]s to the
+//! app through one channel, so the render loop only ever waits on that.
+use crate::{events::Event, utils::Signal};
+use std::{
+    io,
+    sync::{mpsc::Sender, Arc, Condvar, Mutex, PoisonError},
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+/// How often the input thread checks whether it should stop.
+const INPUT_POLL: Duration = Duration::from_millis(50);
+
+pub enum Message {
+    Input(crossterm::event::Event),
+    Tick,
+    /// Queued like a key press, see [`crate::App::enqueue`].
+    Signal(Signal),
+    /// Logged right away, see [`crate::App::push_event`].
+    Event(Event),
+    /// Reading input failed, or a thread feeding the app panicked; the app
+    /// stops with this error.
+    Failed(io::Error),
+}
+
+/// Sends messages to an app from any thread, see [`crate::App::producer`].
+#[derive(Clone)]
+pub struct Producer {
+    sender: Sender<Message>,
+}
+
+impl Producer {
+    pub fn new(sender: Sender<Message>) -> Self {
+        Self { sender }
+    }
+    /// Sends `message`, or returns false if the app is gone.
+    pub fn send(&self, message: Message) -> bool {
+        self.sender.send(message).is_ok()
+    }
+    pub fn signal(&self, signal: Signal) -> bool {
+        self.send(Message::Signal(signal))
+    }
+    pub fn event(&self, event: Event) -> bool {
+        self.send(Message::Event(event))
+    }
+}
+
+/// Tells threads when to finish, on shutdown.
+#[derive(Clone, Default)]
+pub struct Stop(Arc<(Mutex<bool>, Condvar)>);
+
+impl Stop {
+    pub fn is_set(&self) -> bool {
+        *self.0 .0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+    /// Waits up to `timeout` for the stop, and returns whether it came.
+    pub fn wait(&self, timeout: Duration) -> bool {
+        let (stopped, condvar) = &*self.0;
+        let stopped = stopped.lock().unwrap_or_else(PoisonError::into_inner);
+        let (stopped, _) = condvar
+            .wait_timeout_while(stopped, timeout, |stopped| !*stopped)
+            .unwrap_or_else(PoisonError::into_inner);
+        *stopped
+    }
+    fn set(&self) {
+        let (stopped, condvar) = &*self.0;
+        *stopped.lock().unwrap_or_else(PoisonError::into_inner) = true;
+        condvar.notify_all();
+    }
+}
+
+/// Tells the app when the thread it lives on unwinds from a panic.
+struct OnPanic(Producer);
+
+impl Drop for OnPanic {
+    fn drop(&mut self) {
+        if thread::panicking() {
+            let err = io::Error::other("a thread feeding the app panicked");
+            self.0.send(Message::Failed(err));
+        }
+    }
+}
+
+/// The threads feeding an app; they are stopped and joined on shutdown, or
+/// when dropped.
+#[derive(Default)]
+pub struct Threads {
+    stop: Stop,
+    handles: Vec<JoinHandle<()>>,
+}
+
+impl Threads {
+    /// Runs `producer` on a thread of its own. It should return soon once
+    /// its [`Stop`] is set, or shutting down waits for it. If it panics, the
+    /// app stops, as it may no longer get the input it needs, e.g. to quit.
+    pub fn spawn(&mut self, producer: Producer, run: impl FnOnce(Producer, Stop) + Send + 'static) {
+        let stop = self.stop.clone();
+        self.handles.push(thread::spawn(move || {
+            let _panicked = OnPanic(producer.clone());
+            run(producer, stop)
+        }));
+    }
+    /// Forwards terminal input until stopped or reading it fails.
+    pub fn spawn_input(&mut self, producer: Producer) {
+        self.spawn(producer, |producer, stop| {
+            while !stop.is_set() {
+                let message = match crossterm::event::poll(INPUT_POLL) {
+                    Ok(false) => continue,
+                    Ok(true) => match crossterm::event::read() {
+                        Ok(input) => Message::Input(input),
+                        Err(err) => Message::Failed(err),
+                    },
+                    Err(err) => Message::Failed(err),
+                };
+                let failed = matches!(message, Message::Failed(_));
+                if !producer.send(message) || failed {
+                    break;
+                }
+            }
+        });
+    }
+    /// Sends a tick every `rate` until stopped.
+    pub fn spawn_ticks(&mut self, producer: Producer, rate: Duration) {
+        self.spawn(producer, move |producer, stop| {
+            while !stop.wait(rate) {
+                if !producer.send(Message::Tick) {
+                    break;
+                }
+            }
+        });
+    }
+    /// Stops every thread and waits for them to finish. Threads spawned
+    /// afterwards run until the next shutdown.
+    pub fn shutdown(&mut self) -> io::Result<()> {
+        self.stop.set();
+        let panicked = self
+            .handles
+            .drain(..)
+            .filter_map(|handle| handle.join().err())
+            .count();
+        self.stop = Stop::default();
+        match panicked {
+            0 => Ok(()),
+            _ => Err(io::Error::other(format!(
+                "{} thread(s) feeding the app panicked",
+                panicked
+            ))),
+        }
+    }
+}
+
+impl Drop for Threads {
+    fn drop(&mut self) {
+        let _ = self.shutdown();
+    }
+}
@@ -0,0 +1,400 @@
+use regex::Regex;
+use std::{
+    collections::{BTreeSet, VecDeque},
+    fmt,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// How many events the log keeps unless configured otherwise.
+pub const DEFAULT_CAPACITY: usize = 1_000;
+
+/// How many seconds back the log remembers when events arrived.
+pub const RATE_WINDOW: usize = 5 * 60;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    Trace,
+    Debug,
+    Info,
+    Warning,
+    Error,
+    Critical,
+}
+
+impl Severity {
+    /// All severities, from least to most severe.
+    pub const ALL: [Severity; 6] = [
+        Severity::Trace,
+        Severity::Debug,
+        Severity::Info,
+        Severity::Warning,
+        Severity::Error,
+        Severity::Critical,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Severity::Trace => "TRACE",
+            Severity::Debug => "DEBUG",
+            Severity::Info => "INFO",
+            Severity::Warning => "WARNING",
+            Severity::Error => "ERROR",
+            Severity::Critical => "CRITICAL",
+        }
+    }
+    /// The next more severe level, if any.
+    pub fn up(&self) -> Option<Severity> {
+        Severity::ALL.get(*self as usize + 1).copied()
+    }
+    /// The next less severe level, if any.
+    pub fn down(&self) -> Option<Severity> {
+        (*self as usize)
+            .checked_sub(1)
+            .map(|index| Severity::ALL[index])
+    }
+    /// Parses a level name case-insensitively, including common aliases
+    /// such as `warn`, `err` or `fatal`.
+    pub fn from_name(name: &str) -> Option<Severity> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "trace" => Some(Severity::Trace),
+            "debug" => Some(Severity::Debug),
+            "info" | "information" | "notice" => Some(Severity::Info),
+            "warning" | "warn" => Some(Severity::Warning),
+            "error" | "err" => Some(Severity::Error),
+            "critical" | "crit" | "fatal" | "alert" | "emerg" | "emergency" | "panic" => {
+                Some(Severity::Critical)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Where an event stands in triage.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Status {
+    Open,
+    Acknowledged,
+    /// Hidden from the outstanding events until `until` (unix millis).
+    Snoozed {
+        until: u64,
+    },
+    Resolved,
+}
+
+impl Status {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Status::Open => "open",
+            Status::Acknowledged => "acknowledged",
+            Status::Snoozed { .. } => "snoozed",
+            Status::Resolved => "resolved",
+        }
+    }
+}
+
+/// A single entry of the event log.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Event {
+    /// Sequence number, assigned when the event is logged.
+    pub id: u64,
+    /// Milliseconds since the unix epoch.
+    pub timestamp: u64,
+    pub severity: Severity,
+    pub message: String,
+    /// Where the event came from, such as the name of a tailed file.
+    pub source: Option<String>,
+    /// Set by the alert rule the event matched, if it has one.
+    pub label: Option<String>,
+    pub status: Status,
+    /// Structured attributes as key and value, e.g. from a `tracing` event.
+    pub fields: Vec<(String, String)>,
+}
+
+impl Event {
+    /// An event timestamped with the current time.
+    pub fn new(severity: Severity, message: impl Into<String>) -> Self {
+        Self {
+            id: 0,
+            timestamp: now_millis(),
+            severity,
+            message: message.into(),
+            source: None,
+            label: None,
+            status: Status::Open,
+            fields: vec![],
+        }
+    }
+    pub fn with_source(mut self, source: impl Into<String>) -> Self {
+        self.source = Some(source.into());
+        self
+    }
+    /// Whether the event still needs someone to look at it: a warning or
+    /// worse that is open, or whose snooze ran out by `now`.
+    pub fn is_outstanding(&self, now: u64) -> bool {
+        self.severity >= Severity::Warning
+            && match self.status {
+                Status::Open => true,
+                Status::Snoozed { until } => until <= now,
+                Status::Acknowledged | Status::Resolved => false,
+            }
+    }
+}
+
+/// The current time in milliseconds since the unix epoch.
+pub fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+/// Formats the time of day of a millisecond timestamp as `HH:MM:SS` (UTC).
+pub fn format_time(timestamp: u64) -> String {
+    let secs = timestamp / 1_000 % 86_400;
+    format!(
+        "{:02}:{:02}:{:02}",
+        secs / 3_600,
+        secs % 3_600 / 60,
+        secs % 60
+    )
+}
+
+/// A ring buffer of events; once full, every new event evicts the oldest.
+pub struct EventLog {
+    events: VecDeque<Event>,
+    capacity: usize,
+    dropped: u64,
+    /// How many events arrived in each second, as seconds since the unix
+    /// epoch and count, for the last [`RATE_WINDOW`] seconds.
+    arrivals: VecDeque<(u64, u64)>,
+}
+
+impl Default for EventLog {
+    fn default() -> Self {
+        Self::with_capacity(DEFAULT_CAPACITY)
+    }
+}
+
+impl EventLog {
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            events: VecDeque::with_capacity(capacity),
+            capacity: capacity.max(1),
+            dropped: 0,
+            arrivals: VecDeque::new(),
+        }
+    }
+    /// Logs `event`, numbering it after the ones logged before.
+    pub fn push(&mut self, mut event: Event) {
+        event.id = self.dropped + self.events.len() as u64;
+        if self.events.len() == self.capacity {
+            self.events.pop_front();
+            self.dropped += 1;
+        }
+        self.events.push_back(event);
+
+        let second = now_millis() / 1_000;
+        match self.arrivals.back_mut() {
+            Some((last, count)) if *last == second => *count += 1,
+            _ => self.arrivals.push_back((second, 1)),
+        }
+        let oldest = second.saturating_sub(RATE_WINDOW as u64 - 1);
+        while self.arrivals.front().is_some_and(|(at, _)| *at < oldest) {
+            self.arrivals.pop_front();
+        }
+    }
+    /// The event numbered `id`, unless it was evicted.
+    pub fn get_mut(&mut self, id: u64) -> Option<&mut Event> {
+        let index = id.checked_sub(self.dropped)?;
+        self.events.get_mut(index as usize)
+    }
+    /// Events from oldest to newest.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &Event> + ExactSizeIterator {
+        self.events.iter()
+    }
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+    /// How many events were evicted to make room for newer ones.
+    pub fn dropped(&self) -> u64 {
+        self.dropped
+    }
+    /// How many events arrived in each of the last `buckets` spans of
+    /// `bucket` seconds up to `now` in milliseconds, oldest first. Arrivals
+    /// older than [`RATE_WINDOW`] are forgotten, so they count as none.
+    pub fn rate(&self, now: u64, buckets: usize, bucket: u64) -> Vec<u64> {
+        let bucket = bucket.max(1);
+        let first = (now / 1_000 + 1).saturating_sub(buckets as u64 * bucket);
+        let mut rate = vec![0; buckets];
+        for (at, count) in &self.arrivals {
+            if let Some(slot) = at
+                .checked_sub(first)
+                .and_then(|offset| rate.get_mut((offset / bucket) as usize))
+            {
+                *slot += *count;
+            }
+        }
+        rate
+    }
+    /// How many of the events kept have each severity, least severe first.
+    pub fn counts(&self) -> [(Severity, u64); 6] {
+        let mut counts = Severity::ALL.map(|severity| (severity, 0));
+        for event in &self.events {
+            counts[event.severity as usize].1 += 1;
+        }
+        counts
+    }
+}
+
+/// Which events the events pane shows: a minimum severity, severities that
+/// are toggled off, and a text or regex query on the message.
+#[derive(Clone, Debug)]
+pub struct Filter {
+    threshold: Severity,
+    hidden: BTreeSet<Severity>,
+    query: String,
+    regex: bool,
+    /// Only shows [outstanding](Event::is_outstanding) events.
+    outstanding: bool,
+    /// The compiled query in regex mode, or why it does not compile.
+    pattern: Option<Result<Regex, String>>,
+}
+
+impl Default for Filter {
+    fn default() -> Self {
+        Self {
+            threshold: Severity::Trace,
+            hidden: BTreeSet::new(),
+            query: String::new(),
+            regex: false,
+            outstanding: false,
+            pattern: None,
+        }
+    }
+}
+
+impl Filter {
+    pub fn threshold(&self) -> Severity {
+        self.threshold
+    }
+    pub fn set_threshold(&mut self, severity: Severity) {
+        self.threshold = severity;
+    }
+    pub fn raise_threshold(&mut self) {
+        self.threshold = self.threshold.up().unwrap_or(self.threshold);
+    }
+    pub fn lower_threshold(&mut self) {
+        self.threshold = self.threshold.down().unwrap_or(self.threshold);
+    }
+    pub fn is_hidden(&self, severity: Severity) -> bool {
+        self.hidden.contains(&severity)
+    }
+    /// Hides events of `severity`, or shows them again if already hidden.
+    pub fn toggle(&mut self, severity: Severity) {
+        if !self.hidden.remove(&severity) {
+            self.hidden.insert(severity);
+        }
+    }
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+    pub fn set_query(&mut self, query: &str) {
+        self.query = query.to_string();
+        self.compile();
+    }
+    pub fn is_regex(&self) -> bool {
+        self.regex
+    }
+    /// Switches between plain text and regex matching of the query.
+    pub fn toggle_regex(&mut self) {
+        self.regex = !self.regex;
+        self.compile();
+    }
+    fn compile(&mut self) {
+        self.pattern = match self.regex && !self.query.is_empty() {
+            true => Some(Regex::new(&self.query).map_err(|err| err.to_string())),
+            false => None,
+        };
+    }
+    /// Why the regex query does not compile, if it does not.
+    pub fn error(&self) -> Option<&str> {
+        match &self.pattern {
+            Some(Err(err)) => Some(err),
+            _ => None,
+        }
+    }
+    pub fn is_outstanding_only(&self) -> bool {
+        self.outstanding
+    }
+    pub fn toggle_outstanding(&mut self) {
+        self.outstanding = !self.outstanding;
+    }
+    /// Resets everything but the regex mode.
+    pub fn clear(&mut self) {
+        *self = Self {
+            regex: self.regex,
+            ..Self::default()
+        };
+    }
+    /// Whether any events could be filtered out at all.
+    pub fn is_active(&self) -> bool {
+        self.threshold > Severity::Trace
+            || !self.hidden.is_empty()
+            || !self.query.is_empty()
+            || self.outstanding
+    }
+    /// Plain queries match case-insensitively; an invalid regex matches
+    /// everything until it is fixed.
+    pub fn matches(&self, event: &Event) -> bool {
+        if event.severity < self.threshold || self.hidden.contains(&event.severity) {
+            return false;
+        }
+        if self.outstanding && !event.is_outstanding(now_millis()) {
+            return false;
+        }
+        match &self.pattern {
+            Some(Ok(pattern)) => pattern.is_match(&event.message),
+            Some(Err(_)) => true,
+            None if self.regex || self.query.is_empty() => true,
+            None => event
+                .message
+                .to_lowercase()
+                .contains(&self.query.to_lowercase()),
+        }
+    }
+}
+
+impl fmt::Display for Filter {
+    /// A short summary like `>=INFO -DEBUG /disk.*full/`, empty if inactive.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts = vec![];
+        if self.outstanding {
+            parts.push("outstanding".to_string());
+        }
+        if self.threshold > Severity::Trace {
+            parts.push(format!(">={}", self.threshold));
+        }
+        for severity in &self.hidden {
+            parts.push(format!("-{}", severity));
+        }
+        match (self.query.is_empty(), self.regex, self.error()) {
+            (true, _, _) => {}
+            (false, true, None) => parts.push(format!("/{}/", self.query)),
+            (false, true, Some(_)) => parts.push(format!("/{}/ (invalid)", self.query)),
+            (false, false, _) => parts.push(format!("{:?}", self.query)),
+        }
+        f.write_str(&parts.join(" "))
+    }
+}
@@ -0,0 +1,186 @@
+//! Writes events to JSON Lines or CSV files, e.g. to attach to bug reports.
+use crate::{
+    events::{Event, Severity},
+    item,
+    parser::Parser,
+};
+use serde_json::{json, Map, Value};
+use std::{
+    fs,
+    io::{self, BufRead, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    JsonLines,
+    Csv,
+}
+
+impl Format {
+    pub fn from_name(name: &str) -> Option<Format> {
+        match name.to_ascii_lowercase().as_str() {
+            "jsonl" | "json" | "ndjson" => Some(Format::JsonLines),
+            "csv" => Some(Format::Csv),
+            _ => None,
+        }
+    }
+    /// The format matching the extension of `path`, if any.
+    pub fn from_path(path: &Path) -> Option<Format> {
+        Self::from_name(path.extension()?.to_str()?)
+    }
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Format::JsonLines => "jsonl",
+            Format::Csv => "csv",
+        }
+    }
+}
+
+const CSV_HEADER: &str = "id,timestamp,severity,source,label,status,message";
+
+/// Formats a millisecond timestamp as RFC 3339 in UTC, with milliseconds.
+pub fn format_rfc3339(timestamp: u64) -> String {
+    // `YYYY-MM-DD HH:MM:SS UTC`
+    let date = item::format_timestamp(timestamp / 1_000);
+    format!(
+        "{}T{}.{:03}Z",
+        &date[..10],
+        &date[11..19],
+        timestamp % 1_000
+    )
+}
+
+/// Quotes a CSV field if it contains a separator, quote or line break.
+fn csv_field(field: &str) -> String {
+    match field.contains([',', '"', '\n', '\r']) {
+        true => format!("\"{}\"", field.replace('"', "\"\"")),
+        false => field.to_string(),
+    }
+}
+
+/// Writes `events` to `writer`, one per line; CSV starts with a header.
+pub fn write<'a>(
+    writer: &mut impl Write,
+    events: impl IntoIterator<Item = &'a Event>,
+    format: Format,
+) -> io::Result<()> {
+    if format == Format::Csv {
+        writeln!(writer, "{}", CSV_HEADER)?;
+    }
+    for event in events {
+        let timestamp = format_rfc3339(event.timestamp);
+        match format {
+            Format::JsonLines => {
+                let fields = event
+                    .fields
+                    .iter()
+                    .map(|(name, value)| (name.clone(), Value::from(value.as_str())))
+                    .collect::<Map<String, Value>>();
+                let object = json!({
+                    "id": event.id,
+                    "timestamp": timestamp,
+                    "severity": event.severity.name(),
+                    "source": event.source,
+                    "label": event.label,
+                    "status": event.status.name(),
+                    "message": event.message,
+                    "fields": fields,
+                });
+                writeln!(writer, "{}", object)?;
+            }
+            Format::Csv => {
+                let fields = [
+                    event.id.to_string(),
+                    timestamp,
+                    event.severity.name().to_string(),
+                    event.source.clone().unwrap_or_default(),
+                    event.label.clone().unwrap_or_default(),
+                    event.status.name().to_string(),
+                    event.message.clone(),
+                ];
+                let fields = fields.iter().map(|field| csv_field(field));
+                writeln!(writer, "{}", fields.collect::<Vec<String>>().join(","))?;
+            }
+        }
+    }
+    writer.flush()
+}
+
+/// Writes `events` to a file at `path`, replacing any file there, and
+/// creates its directory.
+pub fn to_file<'a>(
+    path: &Path,
+    events: impl IntoIterator<Item = &'a Event>,
+    format: Format,
+) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut writer = BufWriter::new(fs::File::create(path)?);
+    write(&mut writer, events, format)
+}
+
+/// The file name in `dir` for an export made at `timestamp`, like
+/// `events-20240501-120000.jsonl`. See [`to_new_file`] for exports made
+/// within the same second.
+pub fn file_name(dir: &Path, timestamp: u64, format: Format) -> PathBuf {
+    let stamp = format_rfc3339(timestamp)[..19]
+        .replace(['-', ':'], "")
+        .replace('T', "-");
+    dir.join(format!("events-{}.{}", stamp, format.extension()))
+}
+
+/// Writes `events` to a file in `dir` that did not exist yet, named by
+/// [`file_name`], and returns its path. If that name is taken, a number is
+/// added, like `events-20240501-120000-2.jsonl`.
+pub fn to_new_file<'a>(
+    dir: &Path,
+    timestamp: u64,
+    events: impl IntoIterator<Item = &'a Event>,
+    format: Format,
+) -> io::Result<PathBuf> {
+    fs::create_dir_all(dir)?;
+    let first = file_name(dir, timestamp, format);
+    let mut path = first.clone();
+    let mut number = 1;
+    let file = loop {
+        match fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)
+        {
+            Err(err) if err.kind() == io::ErrorKind::AlreadyExists => {
+                number += 1;
+                let stem = first.file_stem().unwrap_or_default().to_string_lossy();
+                path = first.with_file_name(format!("{}-{}.{}", stem, number, format.extension()));
+            }
+            file => break file?,
+        }
+    };
+    write(&mut BufWriter::new(file), events, format)?;
+    Ok(path)
+}
+
+/// Reads every line of `reader` as an event, numbered in order. Lines less
+/// severe than `min_level` are skipped.
+pub fn read(
+    reader: impl BufRead,
+    parser: &Parser,
+    source: Option<&str>,
+    min_level: Severity,
+) -> io::Result<Vec<Event>> {
+    let mut events = vec![];
+    for line in reader.split(b'\n') {
+        let line = line?;
+        let line = line.strip_suffix(b"\r").unwrap_or(&line);
+        let mut event = parser.parse(&String::from_utf8_lossy(line));
+        if event.severity < min_level {
+            continue;
+        }
+        event.id = events.len() as u64;
+        event.source = source.map(str::to_string);
+        events.push(event);
+    }
+    Ok(events)
+}
@@ -0,0 +1,77 @@
+//! Drives an [`App`] headlessly against tui's [`TestBackend`], for tests and
+//! for embedding code that wants to script the app.
+use crate::App;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::io;
+use tui::{backend::TestBackend, buffer::Buffer, Terminal};
+
+/// A key press without modifiers.
+pub fn key(code: KeyCode) -> KeyEvent {
+    KeyEvent::new(code, KeyModifiers::NONE)
+}
+
+/// A key press with the given modifiers.
+pub fn chord(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+    KeyEvent::new(code, modifiers)
+}
+
+/// The rows of `buffer` as plain text, without any styling.
+pub fn buffer_lines(buffer: &Buffer) -> Vec<String> {
+    let width = buffer.area.width as usize;
+    buffer
+        .content
+        .chunks(width.max(1))
+        .map(|row| row.iter().map(|cell| cell.symbol.as_str()).collect())
+        .collect()
+}
+
+pub struct Harness {
+    pub app: App<TestBackend>,
+    pub terminal: Terminal<TestBackend>,
+}
+
+impl Harness {
+    pub fn new(app: App<TestBackend>, width: u16, height: u16) -> io::Result<Self> {
+        let terminal = Terminal::new(TestBackend::new(width, height))?;
+        Ok(Self { app, terminal })
+    }
+    /// Feeds one key to the app, processes it right away rather than on the
+    /// next tick, and renders the resulting frame.
+    pub fn press(&mut self, key: KeyEvent) -> io::Result<&mut Self> {
+        self.app.handle_key(&key);
+        self.app.process_signals()?;
+        self.render()?;
+        Ok(self)
+    }
+    /// Feeds a sequence of keys, rendering after each one like `run_app` does.
+    pub fn keys(&mut self, keys: impl IntoIterator<Item = KeyEvent>) -> io::Result<&mut Self> {
+        for key in keys {
+            self.press(key)?;
+        }
+        Ok(self)
+    }
+    /// Types `text` one char at a time.
+    pub fn type_str(&mut self, text: &str) -> io::Result<&mut Self> {
+        self.keys(text.chars().map(|c| key(KeyCode::Char(c))))
+    }
+    /// Runs one tick, like `run_app` does once per tick rate, and renders.
+    pub fn tick(&mut self) -> io::Result<&mut Self> {
+        self.app.on_tick()?;
+        self.render()
+    }
+    pub fn render(&mut self) -> io::Result<&mut Self> {
+        self.app.draw(&mut self.terminal)?;
+        Ok(self)
+    }
+    pub fn buffer(&self) -> &Buffer {
+        self.terminal.backend().buffer()
+    }
+    /// The last rendered frame as plain text rows.
+    pub fn lines(&self) -> Vec<String> {
+        buffer_lines(self.buffer())
+    }
+    /// Whether `text` appears within a single row of the last rendered frame.
+    pub fn contains(&self, text: &str) -> bool {
+        self.lines().iter().any(|line| line.contains(text))
+    }
+}
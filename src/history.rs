@@ -0,0 +1,116 @@
+use crate::item::Item;
+use std::collections::VecDeque;
+
+/// How many changes are kept for undo unless configured otherwise.
+pub const DEFAULT_DEPTH: usize = 100;
+
+/// A reversible modification of the item list.
+#[derive(Clone, Debug)]
+pub enum Change {
+    Insert {
+        index: usize,
+        item: Item,
+    },
+    Remove {
+        index: usize,
+        item: Item,
+    },
+    Update {
+        index: usize,
+        before: Item,
+        after: Item,
+    },
+    Swap {
+        a: usize,
+        b: usize,
+    },
+}
+
+impl Change {
+    pub fn apply(&self, list: &mut Vec<Item>) {
+        match self {
+            Change::Insert { index, item } => list.insert(*index, item.clone()),
+            Change::Remove { index, .. } => {
+                list.remove(*index);
+            }
+            Change::Update { index, after, .. } => list[*index] = after.clone(),
+            Change::Swap { a, b } => list.swap(*a, *b),
+        }
+    }
+    pub fn revert(&self, list: &mut Vec<Item>) {
+        match self {
+            Change::Insert { index, .. } => {
+                list.remove(*index);
+            }
+            Change::Remove { index, item } => list.insert(*index, item.clone()),
+            Change::Update { index, before, .. } => list[*index] = before.clone(),
+            Change::Swap { a, b } => list.swap(*a, *b),
+        }
+    }
+}
+
+/// The list selection around a change, restored when it is undone or redone.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Selection {
+    pub selected: Option<usize>,
+    pub exit_point: Option<usize>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Entry {
+    pub change: Change,
+    pub before: Selection,
+    pub after: Selection,
+}
+
+/// Undo and redo stacks; the oldest entries are dropped beyond `depth`.
+pub struct History {
+    undo: VecDeque<Entry>,
+    redo: Vec<Entry>,
+    depth: usize,
+}
+
+impl Default for History {
+    fn default() -> Self {
+        Self::new(DEFAULT_DEPTH)
+    }
+}
+
+impl History {
+    pub fn new(depth: usize) -> Self {
+        Self {
+            undo: VecDeque::with_capacity(depth),
+            redo: vec![],
+            depth,
+        }
+    }
+    /// Records a change that was just made, which invalidates anything undone.
+    pub fn record(&mut self, entry: Entry) {
+        self.redo.clear();
+        if self.depth == 0 {
+            return;
+        }
+        if self.undo.len() == self.depth {
+            self.undo.pop_front();
+        }
+        self.undo.push_back(entry);
+    }
+    /// Takes the latest change to revert, moving it onto the redo stack.
+    pub fn undo(&mut self) -> Option<&Entry> {
+        let entry = self.undo.pop_back()?;
+        self.redo.push(entry);
+        self.redo.last()
+    }
+    /// Takes the latest undone change to reapply, moving it back onto the undo stack.
+    pub fn redo(&mut self) -> Option<&Entry> {
+        let entry = self.redo.pop()?;
+        self.undo.push_back(entry);
+        self.undo.back()
+    }
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+}
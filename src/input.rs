@@ -0,0 +1,261 @@
+use crate::utils::{
+    input::{Edit, Kind},
+    list::Operation,
+    Context,
+};
+
+/// A single line of editable text with a cursor, counted in chars.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LineInput {
+    buffer: String,
+    cursor: usize,
+}
+
+impl From<String> for LineInput {
+    /// Starts editing `buffer` with the cursor placed after its last char.
+    fn from(buffer: String) -> Self {
+        let cursor = buffer.chars().count();
+        Self { buffer, cursor }
+    }
+}
+
+impl LineInput {
+    pub fn text(&self) -> &str {
+        &self.buffer
+    }
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+    fn len(&self) -> usize {
+        self.buffer.chars().count()
+    }
+    fn byte_index(&self, char_index: usize) -> usize {
+        self.buffer
+            .char_indices()
+            .nth(char_index)
+            .map(|(index, _)| index)
+            .unwrap_or(self.buffer.len())
+    }
+    /// Char index of the start of the word before the cursor, skipping any
+    /// whitespace directly in front of it.
+    fn word_start(&self) -> usize {
+        let before = self.buffer.chars().take(self.cursor).collect::<Vec<char>>();
+        let mut index = before.len();
+        while index > 0 && before[index - 1].is_whitespace() {
+            index -= 1;
+        }
+        while index > 0 && !before[index - 1].is_whitespace() {
+            index -= 1;
+        }
+        index
+    }
+    /// Char index of the end of the word after the cursor.
+    fn word_end(&self) -> usize {
+        let after = self.buffer.chars().skip(self.cursor).collect::<Vec<char>>();
+        let mut index = 0;
+        while index < after.len() && after[index].is_whitespace() {
+            index += 1;
+        }
+        while index < after.len() && !after[index].is_whitespace() {
+            index += 1;
+        }
+        self.cursor + index
+    }
+    fn remove_range(&mut self, start: usize, end: usize) {
+        let (start_byte, end_byte) = (self.byte_index(start), self.byte_index(end));
+        self.buffer.replace_range(start_byte..end_byte, "");
+        self.cursor = start;
+    }
+    /// Applies a cursor movement or text edit. `Confirm` and `Cancel` are
+    /// left to the owning [`Prompt`].
+    pub fn apply(&mut self, edit: &Edit) {
+        match edit {
+            Edit::Insert(c) => {
+                let index = self.byte_index(self.cursor);
+                self.buffer.insert(index, *c);
+                self.cursor += 1;
+            }
+            Edit::Backspace if self.cursor > 0 => self.remove_range(self.cursor - 1, self.cursor),
+            Edit::Delete if self.cursor < self.len() => {
+                self.remove_range(self.cursor, self.cursor + 1)
+            }
+            Edit::DeleteWord => self.remove_range(self.word_start(), self.cursor),
+            Edit::Left => self.cursor = self.cursor.saturating_sub(1),
+            Edit::Right => self.cursor = (self.cursor + 1).min(self.len()),
+            Edit::WordLeft => self.cursor = self.word_start(),
+            Edit::WordRight => self.cursor = self.word_end(),
+            Edit::Home => self.cursor = 0,
+            Edit::End => self.cursor = self.len(),
+            _ => {}
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Field {
+    Title,
+    Description,
+}
+
+impl Field {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Field::Title => "Title: ",
+            Field::Description => "Description: ",
+        }
+    }
+}
+
+/// The state of the item prompt shown in [`Context::Input`].
+#[derive(Clone, Debug)]
+pub struct Prompt {
+    pub kind: Kind,
+    /// The context to return to once the prompt is confirmed or cancelled.
+    pub origin: Context,
+    pub field: Field,
+    pub title: LineInput,
+    pub description: LineInput,
+}
+
+/// What the app should do after an edit was applied to a [`Prompt`].
+pub enum Outcome {
+    Pending,
+    Confirmed(Operation),
+    Cancelled,
+}
+
+impl Prompt {
+    /// A prompt for a new item, with both fields empty.
+    pub fn new(origin: Context) -> Self {
+        Self::with_values(Kind::New, origin, String::new(), String::new())
+    }
+    pub fn with_values(kind: Kind, origin: Context, title: String, description: String) -> Self {
+        let field = match kind {
+            Kind::Describe => Field::Description,
+            Kind::New | Kind::Rename => Field::Title,
+        };
+        Self {
+            kind,
+            origin,
+            field,
+            title: LineInput::from(title),
+            description: LineInput::from(description),
+        }
+    }
+    pub fn title(&self) -> &'static str {
+        match self.kind {
+            Kind::New => "New item",
+            Kind::Rename => "Rename item",
+            Kind::Describe => "Edit description",
+        }
+    }
+    /// The fields shown for this kind of prompt, in tab order.
+    pub fn fields(&self) -> &'static [Field] {
+        match self.kind {
+            Kind::New => &[Field::Title, Field::Description],
+            Kind::Rename => &[Field::Title],
+            Kind::Describe => &[Field::Description],
+        }
+    }
+    pub fn focused(&self) -> &LineInput {
+        match self.field {
+            Field::Title => &self.title,
+            Field::Description => &self.description,
+        }
+    }
+    fn focused_mut(&mut self) -> &mut LineInput {
+        match self.field {
+            Field::Title => &mut self.title,
+            Field::Description => &mut self.description,
+        }
+    }
+    fn next_field(&self) -> Option<Field> {
+        let fields = self.fields();
+        let position = fields.iter().position(|field| field == &self.field)?;
+        fields.get(position + 1).copied()
+    }
+    fn confirm(&self) -> Operation {
+        let title = self.title.text().trim().to_string();
+        let description = self.description.text().trim().to_string();
+        match self.kind {
+            Kind::New => Operation::New(title, description),
+            Kind::Rename => Operation::Rename(title),
+            Kind::Describe => Operation::Describe(description),
+        }
+    }
+    pub fn apply(&mut self, edit: &Edit) -> Outcome {
+        let needs_title = self.fields().contains(&Field::Title);
+        match edit {
+            Edit::Cancel => Outcome::Cancelled,
+            Edit::NextField => {
+                let fields = self.fields();
+                self.field = self.next_field().unwrap_or(fields[0]);
+                Outcome::Pending
+            }
+            Edit::PrevField => {
+                let fields = self.fields();
+                let position = fields.iter().position(|field| field == &self.field);
+                self.field = match position {
+                    Some(position) if position > 0 => fields[position - 1],
+                    _ => fields[fields.len() - 1],
+                };
+                Outcome::Pending
+            }
+            // An item needs a title, so Enter on an empty one does nothing.
+            Edit::Confirm if needs_title && self.title.text().trim().is_empty() => {
+                self.field = Field::Title;
+                Outcome::Pending
+            }
+            Edit::Confirm => match self.next_field() {
+                Some(field) => {
+                    self.field = field;
+                    Outcome::Pending
+                }
+                None => Outcome::Confirmed(self.confirm()),
+            },
+            edit => {
+                self.focused_mut().apply(edit);
+                Outcome::Pending
+            }
+        }
+    }
+}
+
+/// The incremental search of the events pane, shown in [`Context::Input`].
+/// The filter follows every edit, so the query is live while typing.
+#[derive(Clone, Debug)]
+pub struct Search {
+    /// The context to return to once the search is closed.
+    pub origin: Context,
+    previous: String,
+    pub line: LineInput,
+}
+
+impl Search {
+    /// Starts editing `query`, the one currently filtered by.
+    pub fn new(origin: Context, query: String) -> Self {
+        Self {
+            origin,
+            previous: query.clone(),
+            line: LineInput::from(query),
+        }
+    }
+    pub fn query(&self) -> &str {
+        self.line.text()
+    }
+    /// Applies `edit`, returning false once the search is closed. Cancelling
+    /// puts back the query the search started from.
+    pub fn apply(&mut self, edit: &Edit) -> bool {
+        match edit {
+            Edit::Confirm => false,
+            Edit::Cancel => {
+                self.line = LineInput::from(self.previous.clone());
+                false
+            }
+            edit => {
+                self.line.apply(edit);
+                true
+            }
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A single entry of the list, owned by the app.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Item {
+    pub id: u64,
+    pub title: String,
+    pub description: String,
+    /// Seconds since the unix epoch.
+    pub created: u64,
+    /// Seconds since the unix epoch.
+    pub updated: u64,
+    pub body: Vec<String>,
+}
+
+impl Item {
+    pub fn new(id: u64, title: impl Into<String>, description: impl Into<String>) -> Self {
+        let now = now();
+        Self {
+            id,
+            title: title.into(),
+            description: description.into(),
+            created: now,
+            updated: now,
+            body: vec![],
+        }
+    }
+    pub fn with_body(mut self, body: Vec<String>) -> Self {
+        self.body = body;
+        self
+    }
+    /// Bumps the `updated` timestamp; call after any modification.
+    pub fn touch(&mut self) {
+        self.updated = now();
+    }
+}
+
+/// The current time in seconds since the unix epoch.
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default()
+}
+
+/// An id greater than the id of any of `items`.
+pub fn next_id<'a>(items: impl IntoIterator<Item = &'a Item>) -> u64 {
+    items
+        .into_iter()
+        .map(|item| item.id + 1)
+        .max()
+        .unwrap_or_default()
+}
+
+/// Formats seconds since the unix epoch as `YYYY-MM-DD HH:MM:SS UTC`.
+pub fn format_timestamp(secs: u64) -> String {
+    let (days, rem) = (secs / 86_400, secs % 86_400);
+    // Civil-from-days, see http://howardhinnant.github.io/date_algorithms.html
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02} UTC",
+        year,
+        month,
+        day,
+        rem / 3_600,
+        rem % 3_600 / 60,
+        rem % 60
+    )
+}
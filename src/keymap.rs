@@ -0,0 +1,350 @@
+use crate::utils::{
+    command::Operation as Command,
+    detail::Scroll,
+    events::Operation as EventOp,
+    input::{Edit, Kind},
+    list::{Location, Operation},
+    Action, Context,
+};
+use crate::{events::Severity, export::Format};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::{collections::HashMap, fmt, str::FromStr};
+
+/// The bindings cotermi starts with, as `(context, chord, action)` entries
+/// in the same notation the config file uses.
+const DEFAULT_BINDINGS: &[(Context, &str, &str)] = &[
+    (Context::Default, "q", "quit"),
+    (Context::Default, "Q", "quit"),
+    (Context::Default, "ctrl-z", "suspend"),
+    (Context::Default, "l", "context.list"),
+    (Context::Default, "tab", "context.list"),
+    (Context::Default, "backtab", "context.detail"),
+    (Context::Default, "left", "list.exit-point"),
+    (Context::Default, "up", "list.up"),
+    (Context::Default, "down", "list.down"),
+    (Context::Default, "n", "prompt.new"),
+    (Context::Default, "N", "prompt.new"),
+    (Context::Default, "e", "context.events"),
+    (Context::Default, "R", "command.restart"),
+    (Context::Default, "X", "command.kill"),
+    (Context::Default, "u", "undo"),
+    (Context::Default, "ctrl-r", "redo"),
+    (Context::List, "Q", "quit"),
+    (Context::List, "ctrl-z", "suspend"),
+    (Context::List, "q", "context.default"),
+    (Context::List, "esc", "context.default"),
+    (Context::List, "backtab", "context.default"),
+    (Context::List, "right", "context.detail"),
+    (Context::List, "enter", "context.detail"),
+    (Context::List, "tab", "context.detail"),
+    (Context::List, "up", "list.up"),
+    (Context::List, "down", "list.down"),
+    (Context::List, "shift-up", "list.move-up"),
+    (Context::List, "shift-down", "list.move-down"),
+    (Context::List, "K", "list.move-up"),
+    (Context::List, "J", "list.move-down"),
+    (Context::List, "n", "prompt.new"),
+    (Context::List, "N", "prompt.new"),
+    (Context::List, "r", "prompt.rename"),
+    (Context::List, "e", "prompt.describe"),
+    (Context::List, "d", "list.delete"),
+    (Context::List, "delete", "list.delete"),
+    (Context::List, "u", "undo"),
+    (Context::List, "ctrl-r", "redo"),
+    (Context::Detail, "Q", "quit"),
+    (Context::Detail, "ctrl-z", "suspend"),
+    (Context::Detail, "q", "context.default"),
+    (Context::Detail, "tab", "context.default"),
+    (Context::Detail, "left", "context.list"),
+    (Context::Detail, "esc", "context.list"),
+    (Context::Detail, "backtab", "context.list"),
+    (Context::Detail, "up", "detail.up"),
+    (Context::Detail, "k", "detail.up"),
+    (Context::Detail, "down", "detail.down"),
+    (Context::Detail, "j", "detail.down"),
+    (Context::Detail, "pageup", "detail.page-up"),
+    (Context::Detail, "pagedown", "detail.page-down"),
+    (Context::Detail, "space", "detail.page-down"),
+    (Context::Detail, "home", "detail.top"),
+    (Context::Detail, "g", "detail.top"),
+    (Context::Detail, "end", "detail.bottom"),
+    (Context::Detail, "G", "detail.bottom"),
+    (Context::Events, "Q", "quit"),
+    (Context::Events, "ctrl-z", "suspend"),
+    (Context::Events, "q", "context.default"),
+    (Context::Events, "esc", "context.default"),
+    (Context::Events, "1", "events.toggle-trace"),
+    (Context::Events, "2", "events.toggle-debug"),
+    (Context::Events, "3", "events.toggle-info"),
+    (Context::Events, "4", "events.toggle-warning"),
+    (Context::Events, "5", "events.toggle-error"),
+    (Context::Events, "6", "events.toggle-critical"),
+    (Context::Events, "+", "events.raise-threshold"),
+    (Context::Events, "-", "events.lower-threshold"),
+    (Context::Events, "/", "events.search"),
+    (Context::Events, "r", "events.regex"),
+    (Context::Events, "c", "events.clear-filter"),
+    (Context::Events, "up", "events.up"),
+    (Context::Events, "k", "events.up"),
+    (Context::Events, "down", "events.down"),
+    (Context::Events, "j", "events.down"),
+    (Context::Events, "a", "events.acknowledge"),
+    (Context::Events, "A", "events.acknowledge-all"),
+    (Context::Events, "s", "events.snooze"),
+    (Context::Events, "x", "events.resolve"),
+    (Context::Events, "o", "events.outstanding"),
+    (Context::Events, "t", "events.toggle-stats"),
+    (Context::Events, "w", "events.export-jsonl"),
+    (Context::Events, "W", "events.export-csv"),
+    (Context::Events, "R", "command.restart"),
+    (Context::Events, "X", "command.kill"),
+    (Context::Input, "enter", "input.confirm"),
+    (Context::Input, "esc", "input.cancel"),
+    (Context::Input, "tab", "input.next-field"),
+    (Context::Input, "backtab", "input.prev-field"),
+    (Context::Input, "backspace", "input.backspace"),
+    (Context::Input, "ctrl-backspace", "input.delete-word"),
+    (Context::Input, "alt-backspace", "input.delete-word"),
+    (Context::Input, "ctrl-w", "input.delete-word"),
+    (Context::Input, "delete", "input.delete"),
+    (Context::Input, "left", "input.left"),
+    (Context::Input, "right", "input.right"),
+    (Context::Input, "ctrl-left", "input.word-left"),
+    (Context::Input, "alt-left", "input.word-left"),
+    (Context::Input, "alt-b", "input.word-left"),
+    (Context::Input, "ctrl-right", "input.word-right"),
+    (Context::Input, "alt-right", "input.word-right"),
+    (Context::Input, "alt-f", "input.word-right"),
+    (Context::Input, "home", "input.home"),
+    (Context::Input, "ctrl-a", "input.home"),
+    (Context::Input, "end", "input.end"),
+    (Context::Input, "ctrl-e", "input.end"),
+];
+
+/// A key together with its modifiers, as bound in a [`Keymap`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct KeyChord {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyChord {
+    /// Terminals report shifted letters as uppercase chars, and sometimes also
+    /// set SHIFT, so it is dropped wherever the key itself already carries it.
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        let modifiers =
+            modifiers & (KeyModifiers::SHIFT | KeyModifiers::CONTROL | KeyModifiers::ALT);
+        match code {
+            KeyCode::Char(c) if modifiers.contains(KeyModifiers::SHIFT) => Self {
+                code: KeyCode::Char(c.to_ascii_uppercase()),
+                modifiers: modifiers - KeyModifiers::SHIFT,
+            },
+            KeyCode::BackTab => Self {
+                code,
+                modifiers: modifiers - KeyModifiers::SHIFT,
+            },
+            code => Self { code, modifiers },
+        }
+    }
+}
+
+impl From<&KeyEvent> for KeyChord {
+    fn from(key: &KeyEvent) -> Self {
+        Self::new(key.code, key.modifiers)
+    }
+}
+
+impl FromStr for KeyChord {
+    type Err = String;
+    /// Parses chords like `q`, `Q`, `ctrl-r`, `shift-up` or `alt+backspace`.
+    fn from_str(chord: &str) -> Result<Self, Self::Err> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = chord;
+        'strip: loop {
+            for (prefix, modifier) in [
+                ("ctrl", KeyModifiers::CONTROL),
+                ("alt", KeyModifiers::ALT),
+                ("shift", KeyModifiers::SHIFT),
+            ] {
+                let stripped = rest
+                    .get(..prefix.len())
+                    .filter(|head| head.eq_ignore_ascii_case(prefix))
+                    .and_then(|_| rest[prefix.len()..].strip_prefix(['-', '+']));
+                if let Some(stripped) = stripped.filter(|stripped| !stripped.is_empty()) {
+                    modifiers |= modifier;
+                    rest = stripped;
+                    continue 'strip;
+                }
+            }
+            break;
+        }
+        let mut chars = rest.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match rest.to_ascii_lowercase().as_str() {
+                "enter" | "return" => KeyCode::Enter,
+                "esc" | "escape" => KeyCode::Esc,
+                "tab" => KeyCode::Tab,
+                "backtab" => KeyCode::BackTab,
+                "backspace" => KeyCode::Backspace,
+                "delete" | "del" => KeyCode::Delete,
+                "insert" | "ins" => KeyCode::Insert,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" => KeyCode::PageUp,
+                "pagedown" => KeyCode::PageDown,
+                "space" => KeyCode::Char(' '),
+                "minus" => KeyCode::Char('-'),
+                name => match name.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+                    Some(n) if (1..=24).contains(&n) => KeyCode::F(n),
+                    _ => return Err(format!("unknown key {:?} in {:?}", rest, chord)),
+                },
+            },
+        };
+        Ok(Self::new(code, modifiers))
+    }
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (modifier, name) in [
+            (KeyModifiers::CONTROL, "ctrl-"),
+            (KeyModifiers::ALT, "alt-"),
+            (KeyModifiers::SHIFT, "shift-"),
+        ] {
+            if self.modifiers.contains(modifier) {
+                f.write_str(name)?;
+            }
+        }
+        match self.code {
+            KeyCode::Char(' ') => f.write_str("space"),
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::F(n) => write!(f, "f{}", n),
+            code => write!(f, "{}", format!("{:?}", code).to_lowercase()),
+        }
+    }
+}
+
+/// Parses an action name as used in the config file. `None` unbinds a key.
+pub fn parse_action(name: &str) -> Result<Option<Action>, String> {
+    let action = match name {
+        "none" => return Ok(None),
+        "quit" => Action::Quit,
+        "suspend" => Action::Suspend,
+        "undo" => Action::Undo,
+        "redo" => Action::Redo,
+        "context.default" => Action::ChangeContext(Context::Default),
+        "context.list" => Action::ChangeContext(Context::List),
+        "context.detail" => Action::ChangeContext(Context::Detail),
+        "context.events" => Action::ChangeContext(Context::Events),
+        "list.exit-point" => Action::ListSignal(Operation::Go(Location::ExitPoint)),
+        "list.up" => Action::ListSignal(Operation::Go(Location::Up)),
+        "list.down" => Action::ListSignal(Operation::Go(Location::Down)),
+        "list.move-up" => Action::ListSignal(Operation::MoveUp),
+        "list.move-down" => Action::ListSignal(Operation::MoveDown),
+        "list.delete" => Action::ListSignal(Operation::Delete),
+        "prompt.new" => Action::Prompt(Kind::New),
+        "prompt.rename" => Action::Prompt(Kind::Rename),
+        "prompt.describe" => Action::Prompt(Kind::Describe),
+        "detail.up" => Action::DetailSignal(Scroll::Up),
+        "detail.down" => Action::DetailSignal(Scroll::Down),
+        "detail.page-up" => Action::DetailSignal(Scroll::PageUp),
+        "detail.page-down" => Action::DetailSignal(Scroll::PageDown),
+        "detail.top" => Action::DetailSignal(Scroll::Top),
+        "detail.bottom" => Action::DetailSignal(Scroll::Bottom),
+        "events.up" => Action::EventSignal(EventOp::Up),
+        "events.down" => Action::EventSignal(EventOp::Down),
+        "events.acknowledge" => Action::EventSignal(EventOp::Acknowledge),
+        "events.acknowledge-all" => Action::EventSignal(EventOp::AcknowledgeAll),
+        "events.snooze" => Action::EventSignal(EventOp::Snooze),
+        "events.resolve" => Action::EventSignal(EventOp::Resolve),
+        "events.outstanding" => Action::EventSignal(EventOp::ToggleOutstanding),
+        "events.toggle-stats" => Action::EventSignal(EventOp::ToggleStats),
+        "events.export-jsonl" => Action::EventSignal(EventOp::Export(Format::JsonLines)),
+        "events.export-csv" => Action::EventSignal(EventOp::Export(Format::Csv)),
+        "events.raise-threshold" => Action::EventSignal(EventOp::RaiseThreshold),
+        "events.lower-threshold" => Action::EventSignal(EventOp::LowerThreshold),
+        "events.search" => Action::EventSignal(EventOp::Search),
+        "events.regex" => Action::EventSignal(EventOp::ToggleRegex),
+        "events.clear-filter" => Action::EventSignal(EventOp::ClearFilter),
+        "command.restart" => Action::CommandSignal(Command::Restart),
+        "command.kill" => Action::CommandSignal(Command::Kill),
+        "input.confirm" => Action::InputSignal(Edit::Confirm),
+        "input.cancel" => Action::InputSignal(Edit::Cancel),
+        "input.next-field" => Action::InputSignal(Edit::NextField),
+        "input.prev-field" => Action::InputSignal(Edit::PrevField),
+        "input.backspace" => Action::InputSignal(Edit::Backspace),
+        "input.delete" => Action::InputSignal(Edit::Delete),
+        "input.delete-word" => Action::InputSignal(Edit::DeleteWord),
+        "input.left" => Action::InputSignal(Edit::Left),
+        "input.right" => Action::InputSignal(Edit::Right),
+        "input.word-left" => Action::InputSignal(Edit::WordLeft),
+        "input.word-right" => Action::InputSignal(Edit::WordRight),
+        "input.home" => Action::InputSignal(Edit::Home),
+        "input.end" => Action::InputSignal(Edit::End),
+        name => match name
+            .strip_prefix("events.toggle-")
+            .and_then(Severity::from_name)
+        {
+            Some(severity) => Action::EventSignal(EventOp::Toggle(severity)),
+            None => return Err(format!("unknown action {:?}", name)),
+        },
+    };
+    Ok(Some(action))
+}
+
+/// Maps key chords to actions, separately for every [`Context`].
+#[derive(Clone)]
+pub struct Keymap {
+    bindings: HashMap<(Context, KeyChord), Action>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let mut keymap = Self::empty();
+        for (context, chord, action) in DEFAULT_BINDINGS {
+            let chord = chord.parse().expect("default key bindings are valid");
+            let action = parse_action(action).expect("default key bindings are valid");
+            keymap.bind(*context, chord, action);
+        }
+        keymap
+    }
+}
+
+impl Keymap {
+    /// A keymap without any bindings; only typing into prompts still works.
+    pub fn empty() -> Self {
+        Self {
+            bindings: HashMap::new(),
+        }
+    }
+    /// Binds `chord` to `action` in `context`, or unbinds it for `None`.
+    pub fn bind(&mut self, context: Context, chord: KeyChord, action: Option<Action>) {
+        match action {
+            Some(action) => self.bindings.insert((context, chord), action),
+            None => self.bindings.remove(&(context, chord)),
+        };
+    }
+    /// The action bound to `key` in `context`. Unbound printable keys type
+    /// themselves into prompts.
+    pub fn action(&self, context: Context, key: &KeyEvent) -> Option<Action> {
+        let chord = KeyChord::from(key);
+        match self.bindings.get(&(context, chord)) {
+            Some(action) => Some(action.clone()),
+            None => match (context, chord.code) {
+                (Context::Input, KeyCode::Char(c))
+                    if !chord
+                        .modifiers
+                        .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+                {
+                    Some(Action::InputSignal(Edit::Insert(c)))
+                }
+                _ => None,
+            },
+        }
+    }
+}
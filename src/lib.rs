@@ -1,25 +1,52 @@
-pub mod ctxt;
-pub mod list;
-pub mod signal;
+pub mod cli;
+pub mod command;
+pub mod config;
+pub mod event_loop;
+pub mod events;
+pub mod export;
+pub mod harness;
+pub mod history;
+pub mod input;
+pub mod item;
+pub mod keymap;
+pub mod parser;
+pub mod paths;
+pub mod rules;
+pub mod storage;
+pub mod tail;
+pub mod terminal;
+pub mod theme;
+pub mod trace;
 pub mod utils;
-use crossterm::{
-    event::{self, DisableMouseCapture, Event, KeyCode, KeyEvent},
-    execute,
-    terminal::{disable_raw_mode, LeaveAlternateScreen},
-};
+use crossterm::event::{Event, KeyEvent};
+use history::{Change, Entry, History, Selection};
+use item::Item;
 use std::{
+    collections::{BTreeMap, VecDeque},
     io,
+    sync::mpsc::{self, Receiver},
     time::{Duration, Instant},
 };
+use theme::Theme;
 use tui::{
     backend::Backend,
-    layout::{Constraint, Direction, Layout},
-    style::{Color, Modifier, Style},
+    layout::{Constraint, Direction, Layout, Rect},
+    style::Style,
     text::{Span, Spans},
-    widgets::{Block, Borders, List, ListItem, ListState},
+    widgets::{
+        BarChart, Block, BorderType, Borders, Clear, List, ListItem, ListState, Paragraph,
+        Sparkline, Wrap,
+    },
     Frame, Terminal,
 };
 
+/// How long the events pane flashes after an alert rule asked for it.
+const FLASH: Duration = Duration::from_secs(3);
+/// The statistics header needs a terminal at least this tall.
+const STATS_MIN_HEIGHT: u16 = 24;
+/// How long snoozed events stay out of the outstanding ones, in millis.
+const SNOOZE: u64 = 15 * 60 * 1_000;
+
 struct StatefulList<I> {
     state: ListState,
     exit_point: Option<usize>,
@@ -35,6 +62,10 @@ impl<I> StatefulList<I> {
         }
     }
     fn next(&mut self) -> io::Result<()> {
+        if self.list.is_empty() {
+            self.state.select(None);
+            return Ok(());
+        }
         let next_index = match self.state.selected() {
             Some(current_index) => {
                 if current_index >= self.list.len() - 1 {
@@ -49,6 +80,10 @@ impl<I> StatefulList<I> {
         Ok(())
     }
     fn prev(&mut self) -> io::Result<()> {
+        if self.list.is_empty() {
+            self.state.select(None);
+            return Ok(());
+        }
         let prev_index = match self.state.selected() {
             Some(current_index) => {
                 if current_index == 0 {
@@ -68,6 +103,10 @@ impl<I> StatefulList<I> {
         Ok(())
     }
     fn reselect(&mut self) -> io::Result<()> {
+        if self.list.is_empty() {
+            self.state.select(None);
+            return Ok(());
+        }
         let entry_point = match self.exit_point {
             Some(exit_point) => {
                 if exit_point >= self.list.len() {
@@ -89,6 +128,88 @@ impl<I> StatefulList<I> {
         self.reselect()?;
         self.prev()
     }
+    fn push(&mut self, item: I) -> io::Result<()> {
+        self.list.push(item);
+        Ok(())
+    }
+    fn focused(&self) -> Option<&I> {
+        self.state
+            .selected()
+            .or(self.exit_point)
+            .and_then(|index| self.list.get(index))
+    }
+    fn selected_mut(&mut self) -> Option<&mut I> {
+        self.state
+            .selected()
+            .and_then(|index| self.list.get_mut(index))
+    }
+    /// Removes the selected item; the selection stays at the same position,
+    /// or moves to the new last item when the old last one was removed.
+    fn remove(&mut self) -> Option<I> {
+        let index = self
+            .state
+            .selected()
+            .filter(|&index| index < self.list.len())?;
+        let item = self.list.remove(index);
+        let selected = match self.list.len() {
+            0 => None,
+            len => Some(index.min(len - 1)),
+        };
+        self.state.select(selected);
+        Some(item)
+    }
+    /// Swaps the selected item with the one above it, keeping it selected.
+    fn move_up(&mut self) -> io::Result<()> {
+        if let Some(index) = self.state.selected().filter(|&index| index > 0) {
+            self.list.swap(index, index - 1);
+            self.state.select(Some(index - 1));
+        }
+        Ok(())
+    }
+    /// Swaps the selected item with the one below it, keeping it selected.
+    fn move_down(&mut self) -> io::Result<()> {
+        if let Some(index) = self
+            .state
+            .selected()
+            .filter(|&index| index + 1 < self.list.len())
+        {
+            self.list.swap(index, index + 1);
+            self.state.select(Some(index + 1));
+        }
+        Ok(())
+    }
+}
+/// Scroll position of the detail pane, reset whenever another item is shown.
+#[derive(Default)]
+struct DetailState {
+    scroll: u16,
+    shown: Option<u64>,
+    /// Bounds measured on the last render, used to clamp scrolling.
+    max_scroll: u16,
+    page: u16,
+}
+
+impl DetailState {
+    fn show(&mut self, id: Option<u64>) {
+        if self.shown != id {
+            self.shown = id;
+            self.scroll = 0;
+        }
+    }
+    fn scroll(&mut self, scroll: &utils::detail::Scroll) -> io::Result<()> {
+        use utils::detail::Scroll;
+        let page = self.page.max(1);
+        self.scroll = match scroll {
+            Scroll::Up => self.scroll.saturating_sub(1),
+            Scroll::Down => self.scroll.saturating_add(1),
+            Scroll::PageUp => self.scroll.saturating_sub(page),
+            Scroll::PageDown => self.scroll.saturating_add(page),
+            Scroll::Top => 0,
+            Scroll::Bottom => self.max_scroll,
+        }
+        .min(self.max_scroll);
+        Ok(())
+    }
 }
 // #[derive(Clone, Copy)]
 // pub enum ListDir {
@@ -150,111 +271,311 @@ impl<I> StatefulList<I> {
 //     }
 // }
 
-pub struct App<'a, B> {
+pub struct App<B> {
     // terminal: Terminal<B>,
-    items: StatefulList<(&'a str, usize)>,
-    events: Vec<(&'a str, &'a str)>,
+    items: StatefulList<Item>,
+    events: events::EventLog,
+    filter: events::Filter,
+    /// The id of the selected event; none follows the newest events.
+    event_cursor: Option<u64>,
+    /// Whether the event statistics header is shown, given room for it.
+    show_stats: bool,
+    /// Whether the last frame had room for the statistics header.
+    stats_drawn: bool,
+    /// Where exports are written; the data directory if unset.
+    export_dir: Option<std::path::PathBuf>,
+    tails: Vec<tail::Tail>,
+    commands: Vec<command::Command>,
+    /// Messages from the threads in `threads`, and from other producers such
+    /// as a [`trace::Layer`].
+    inbox: Receiver<event_loop::Message>,
+    producer: event_loop::Producer,
+    threads: event_loop::Threads,
+    parser: parser::Parser,
+    rules: rules::Rules,
+    alerts: BTreeMap<String, usize>,
+    flash_until: Option<Instant>,
+    bell: bool,
     // state: Option<ActiveBlock>,
-    context: utils::context::Context,
-    // queue: Vec<Signal>,
+    context: utils::Context,
+    prompt: Option<input::Prompt>,
+    search: Option<input::Search>,
+    history: History,
+    detail: DetailState,
+    keymap: keymap::Keymap,
+    theme: Theme,
+    storage: Option<storage::Storage>,
+    running: bool,
+    /// Whether anything changed since the last frame was drawn.
+    dirty: bool,
+    /// The second of the last tick, in seconds since the unix epoch.
+    clock: u64,
+    max_fps: u32,
+    /// Signals waiting for the next tick, see [`App::process_signals`].
+    queue: VecDeque<utils::Signal>,
+    /// Set by [`utils::Action::Suspend`] for `run_app` to act on.
+    suspend: bool,
     phantom_data: std::marker::PhantomData<B>,
 }
-impl<'a, B: Backend> Default for App<'a, B> {
+fn default_items() -> Vec<Item> {
+    [1, 2, 1, 3, 1, 4, 1, 3, 1, 6]
+        .into_iter()
+        .zip(0..)
+        .map(|(lines, id)| {
+            Item::new(id, format!("Item{}", id), "").with_body(vec![
+                "Lorem ipsum dolor sit amet, consectetur adipiscing elit."
+                    .to_string();
+                lines
+            ])
+        })
+        .collect()
+}
+impl<B: Backend> Default for App<B> {
     fn default() -> Self {
+        let (sender, inbox) = mpsc::channel();
         Self {
             // terminal,
-            items: StatefulList::with_items(vec![
-                ("Item0", 1),
-                ("Item1", 2),
-                ("Item2", 1),
-                ("Item3", 3),
-                ("Item4", 1),
-                ("Item5", 4),
-                ("Item6", 1),
-                ("Item7", 3),
-                ("Item8", 1),
-                ("Item9", 6),
-            ]),
-            events: vec![
-                ("Event1", "INFO"),
-                ("Event2", "INFO"),
-                ("Event3", "CRITICAL"),
-                ("Event4", "ERROR"),
-            ],
+            items: StatefulList::with_items(default_items()),
+            events: events::EventLog::default(),
+            filter: events::Filter::default(),
+            event_cursor: None,
+            show_stats: true,
+            stats_drawn: false,
+            export_dir: None,
+            tails: vec![],
+            commands: vec![],
+            inbox,
+            producer: event_loop::Producer::new(sender),
+            threads: event_loop::Threads::default(),
+            parser: parser::Parser::default(),
+            rules: rules::Rules::default(),
+            alerts: BTreeMap::new(),
+            flash_until: None,
+            bell: false,
             // state: None,
             context: utils::Context::Default,
-            // queue: vec![],
-            phantom_data: std::marker::PhantomData::default(),
+            prompt: None,
+            search: None,
+            history: History::default(),
+            detail: DetailState::default(),
+            keymap: keymap::Keymap::default(),
+            theme: Theme::default(),
+            storage: None,
+            running: true,
+            dirty: true,
+            clock: 0,
+            max_fps: config::DEFAULT_MAX_FPS,
+            queue: VecDeque::new(),
+            suspend: false,
+            phantom_data: std::marker::PhantomData,
         }
     }
 }
-impl<'a, B: Backend> App<'a, B>
-where
-    B: io::Write,
-{
+impl<B: Backend> App<B> {
     pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn with_items(items: Vec<Item>) -> Self {
         Self {
-            // terminal,
-            items: StatefulList::with_items(vec![
-                ("Item0", 1),
-                ("Item1", 2),
-                ("Item2", 1),
-                ("Item3", 3),
-                ("Item4", 1),
-                ("Item5", 4),
-                ("Item6", 1),
-                ("Item7", 3),
-                ("Item8", 1),
-                ("Item9", 6),
-            ]),
-            events: vec![
-                ("Event1", "INFO"),
-                ("Event2", "INFO"),
-                ("Event3", "CRITICAL"),
-                ("Event4", "ERROR"),
-            ],
-            // state: None,
-            context: utils::Context::Default,
-            // queue: vec![],
-            phantom_data: std::marker::PhantomData::default(),
-        }
-    }
-    fn quit(&mut self, terminal: &mut Terminal<B>) -> io::Result<()> {
-        disable_raw_mode()?;
-        execute!(
-            terminal.backend_mut(),
-            LeaveAlternateScreen,
-            DisableMouseCapture,
-        )?;
-        terminal.show_cursor()?;
+            items: StatefulList::with_items(items),
+            ..Self::default()
+        }
+    }
+    /// Creates an app whose items are loaded from, and saved back to, `storage`.
+    pub fn with_storage(storage: storage::Storage) -> io::Result<Self> {
+        let items = storage.load()?;
+        Ok(Self {
+            items: StatefulList::with_items(items),
+            storage: Some(storage),
+            ..Self::default()
+        })
+    }
+    /// Applies the user's settings, such as key bindings and the theme.
+    pub fn with_config(mut self, config: config::Config) -> Self {
+        self.keymap = config.keymap;
+        self.theme = config.theme;
+        self.parser = config.parser;
+        self.rules = config.rules;
+        self.max_fps = config.max_fps.max(1);
+        self
+    }
+    /// Caps how often `run_app` draws, however fast events arrive.
+    pub fn with_max_fps(mut self, fps: u32) -> Self {
+        self.max_fps = fps.max(1);
+        self
+    }
+    pub fn items(&self) -> &[Item] {
+        &self.items.list
+    }
+    /// The selected item, or the one at the exit point while deselected.
+    pub fn focused_item(&self) -> Option<&Item> {
+        self.items.focused()
+    }
+    /// Replaces the event log, e.g. with one of a different capacity.
+    pub fn with_event_log(mut self, events: events::EventLog) -> Self {
+        self.events = events;
+        self
+    }
+    /// A `tracing` layer that logs into this app's events pane, e.g. with
+    /// `tracing_subscriber::registry().with(app.tracing_layer()).init()`.
+    /// Its events show up on the next tick.
+    pub fn tracing_layer(&self) -> trace::Layer {
+        trace::Layer::new(self.producer())
+    }
+    /// A handle for sending this app signals and events from any thread;
+    /// they are handled on the next tick.
+    pub fn producer(&self) -> event_loop::Producer {
+        self.producer.clone()
+    }
+    /// Runs `run` on a thread of its own with a [`event_loop::Producer`] for
+    /// this app. `run_app` stops and joins it before returning.
+    pub fn spawn(
+        &mut self,
+        run: impl FnOnce(event_loop::Producer, event_loop::Stop) + Send + 'static,
+    ) {
+        self.threads.spawn(self.producer(), run);
+    }
+    /// Follows the log file at `path`, adding every new line as an event
+    /// parsed by the configured [`parser::Parser`].
+    pub fn with_tail(mut self, path: impl Into<std::path::PathBuf>) -> Self {
+        self.tails.push(tail::Tail::new(path));
+        self
+    }
+    pub fn with_export_dir(mut self, dir: impl Into<std::path::PathBuf>) -> Self {
+        self.export_dir = Some(dir.into());
+        self
+    }
+    /// Runs `line` through the shell and streams its output into the events.
+    /// Lines on stderr that do not name a level are logged as warnings.
+    pub fn with_command(mut self, line: impl Into<String>) -> Self {
+        let mut command = command::Command::new(line);
+        if let Err(err) = command.start() {
+            self.command_failed(&command, "start", err);
+        }
+        self.commands.push(command);
+        self
+    }
+    /// Appends an event to the log shown in the events pane, after applying
+    /// the alert rules to it.
+    pub fn push_event(&mut self, mut event: events::Event) {
+        if let Some(effects) = self.rules.apply(&mut event) {
+            let label = event.label.clone().unwrap_or_else(|| "alert".to_string());
+            *self.alerts.entry(label).or_default() += 1;
+            for effect in effects {
+                match effect {
+                    rules::Effect::Flash => self.flash_until = Some(Instant::now() + FLASH),
+                    rules::Effect::Bell => self.bell = true,
+                }
+            }
+        }
+        self.events.push(event);
+        self.dirty = true;
+    }
+    /// Queues `signal` to be processed on the next tick.
+    pub fn enqueue(&mut self, signal: utils::Signal) {
+        self.queue.push_back(signal);
+    }
+    /// Processes every queued signal: the top-level ones first, then the
+    /// contextual ones in order, unless one of the former quit the app.
+    pub fn process_signals(&mut self) -> io::Result<()> {
+        let (top_level, contextual): (Vec<_>, Vec<_>) = self
+            .queue
+            .drain(..)
+            .partition(|signal| signal.stype == utils::SType::TopLevel);
+        for signal in top_level.into_iter().chain(contextual) {
+            if !self.running {
+                break;
+            }
+            self.process(&signal.action)?;
+            self.dirty = true;
+        }
         Ok(())
     }
+    /// Whether the app asked to be suspended, which clears the request.
+    pub fn take_suspend(&mut self) -> bool {
+        std::mem::take(&mut self.suspend)
+    }
+    /// How many events matched an alert rule, by label.
+    pub fn alerts(&self) -> &BTreeMap<String, usize> {
+        &self.alerts
+    }
+    /// Whether the bell should be rung, which clears the request.
+    pub fn take_bell(&mut self) -> bool {
+        std::mem::take(&mut self.bell)
+    }
+    /// Whether a frame is due: something changed since the last one.
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+    fn flashing(&self) -> bool {
+        self.flash_until.is_some_and(|until| Instant::now() < until)
+    }
+    pub fn events(&self) -> &events::EventLog {
+        &self.events
+    }
+    pub fn filter(&self) -> &events::Filter {
+        &self.filter
+    }
+    pub fn context(&self) -> utils::Context {
+        self.context
+    }
+    pub fn selected(&self) -> Option<usize> {
+        self.items.state.selected()
+    }
+    pub fn exit_point(&self) -> Option<usize> {
+        self.items.exit_point
+    }
+    /// False once a quit was processed; `run_app` returns at that point.
+    pub fn is_running(&self) -> bool {
+        self.running
+    }
+    fn save(&self) -> io::Result<()> {
+        match &self.storage {
+            Some(storage) => storage.save(&self.items.list),
+            None => Ok(()),
+        }
+    }
+    /// Stops the app; restoring the terminal is left to whoever set it up.
+    fn quit(&mut self) -> io::Result<()> {
+        self.running = false;
+        self.save()
+    }
 
-    fn process(
-        &mut self,
-        signal: &utils::Signal<'a>,
-        terminal: &mut Terminal<B>,
-    ) -> io::Result<()> {
+    fn process(&mut self, action: &utils::Action) -> io::Result<()> {
         use utils::list::*;
+        use utils::Action;
         use utils::Context;
-        use utils::Signal;
-        match signal {
-            Signal::Quit => self.quit(terminal),
-            Signal::ChangeContext(ctxt) => match (&self.context, ctxt) {
-                (Context::List, target_ctxt) if target_ctxt != &Context::List => {
+        match action {
+            Action::Quit => self.quit(),
+            // The terminal catches up with its new size on the next draw
+            Action::Resize(..) => Ok(()),
+            Action::Suspend => {
+                self.suspend = true;
+                Ok(())
+            }
+            Action::Key(key) => match self.keymap.action(self.context, key) {
+                Some(action) => self.process(&action),
+                None => Ok(()),
+            },
+            Action::ChangeContext(ctxt) => match (&self.context, ctxt) {
+                // Bindings may move to the context already focused
+                (current_ctxt, target_ctxt) if current_ctxt == target_ctxt => Ok(()),
+                // Prompts are only entered and left through Action::Prompt
+                (Context::Input, _) | (_, Context::Input) => Ok(()),
+                (Context::List, target_ctxt) => {
                     self.context = *target_ctxt;
                     self.items.deselect()
                 }
-                (current_ctxt, target_ctxt) if current_ctxt == target_ctxt => {
-                    panic!("Attempted circular context movement!")
-                }
                 (_, Context::List) => {
                     self.context = Context::List;
                     self.items.reselect()
                 }
-                (_, _) => panic!("Non-existent context movement!"),
+                (_, target_ctxt) => {
+                    self.context = *target_ctxt;
+                    Ok(())
+                }
             },
-            Signal::ListSignal(Operation::Go(location)) => match &self.context {
+            Action::ListSignal(Operation::Go(location)) => match &self.context {
                 Context::List => match location {
                     Location::Up => self.items.prev(),
                     Location::Down => self.items.next(),
@@ -265,148 +586,879 @@ where
                     Location::Down => self.items.reselect_next(),
                     Location::ExitPoint => self.items.reselect(),
                 },
+                Context::Input | Context::Detail | Context::Events => Ok(()),
             },
-            // Signal::ListNav(direction) => match &self.ctxt {
-            //     Ctxt::List => match direction {
-            //         ListDir::Up => self.items.prev(),
-            //         ListDir::Down => self.items.next(),
-            //         ListDir::ExitPoint => self.items.reselect(),
-            //     },
-            //     Ctxt::Default => {
-            //         self.ctxt = Ctxt::List;
-            //         match direction {
-            //             ListDir::Up => self.items.reselect_prev(),
-            //             ListDir::Down => self.items.reselect_next(),
-            //             ListDir::ExitPoint => self.items.reselect(),
-            //         }
-            //     }
-            //     _ => panic!("Signal::ListNav while not in list!"),
-            // },
-            Signal::ListSignal(Operation::New(title, _)) => match &self.context {
-                Context::List => Ok(self.items.list.push((title, 1usize))),
-                Context::Default => todo!(),
+            Action::ListSignal(Operation::New(title, description)) => {
+                let before = self.selection();
+                let id = item::next_id(&self.items.list);
+                let item = Item::new(id, title, description);
+                self.items.push(item.clone())?;
+                let index = self.items.list.len() - 1;
+                match &self.context {
+                    Context::List => self.items.state.select(Some(index)),
+                    _ => self.items.exit_point = Some(index),
+                }
+                self.record(Change::Insert { index, item }, before)
+            }
+            Action::ListSignal(Operation::Rename(title)) => {
+                self.update_selected(|item| item.title = title.clone())
+            }
+            Action::ListSignal(Operation::Describe(description)) => {
+                self.update_selected(|item| item.description = description.clone())
+            }
+            Action::ListSignal(Operation::Delete) => {
+                let before = self.selection();
+                match (before.selected, self.items.remove()) {
+                    (Some(index), Some(item)) => {
+                        self.record(Change::Remove { index, item }, before)
+                    }
+                    _ => Ok(()),
+                }
+            }
+            Action::ListSignal(Operation::MoveUp) => {
+                let before = self.selection();
+                self.items.move_up()?;
+                self.record_move(before)
+            }
+            Action::ListSignal(Operation::MoveDown) => {
+                let before = self.selection();
+                self.items.move_down()?;
+                self.record_move(before)
+            }
+            Action::Undo => match self.history.undo() {
+                Some(Entry { change, before, .. }) => {
+                    change.revert(&mut self.items.list);
+                    let before = *before;
+                    self.restore(before);
+                    self.save()
+                }
+                None => Ok(()),
             },
+            Action::Redo => match self.history.redo() {
+                Some(Entry { change, after, .. }) => {
+                    change.apply(&mut self.items.list);
+                    let after = *after;
+                    self.restore(after);
+                    self.save()
+                }
+                None => Ok(()),
+            },
+            Action::DetailSignal(scroll) => self.detail.scroll(scroll),
+            Action::EventSignal(operation) => self.update_events(operation),
+            Action::CommandSignal(operation) => self.control_commands(operation),
+            Action::Prompt(kind) => self.open_prompt(*kind),
+            Action::InputSignal(edit) => {
+                let outcome = match (&mut self.prompt, &mut self.search) {
+                    (Some(prompt), _) => prompt.apply(edit),
+                    (None, Some(search)) => {
+                        let open = search.apply(edit);
+                        self.filter.set_query(search.query());
+                        if !open {
+                            self.close_search();
+                        }
+                        return Ok(());
+                    }
+                    (None, None) => return Ok(()),
+                };
+                match outcome {
+                    input::Outcome::Pending => Ok(()),
+                    input::Outcome::Cancelled => self.close_prompt(),
+                    input::Outcome::Confirmed(operation) => {
+                        self.close_prompt()?;
+                        self.process(&Action::ListSignal(operation))
+                    }
+                }
+            }
         }
     }
 
-    fn poll(&'a mut self, terminal: &mut Terminal<B>) -> io::Result<()> {
-        if let Event::Key(key) = event::read()? {
-            if let Some(signal) = self.context.process_input(&key) {
-                self.process(&signal, terminal)
-            } else {
-                Ok(())
+    fn selection(&self) -> Selection {
+        Selection {
+            selected: self.items.state.selected(),
+            exit_point: self.items.exit_point,
+        }
+    }
+
+    /// Puts back a recorded selection. Outside of the list context nothing is
+    /// highlighted, so the selected item becomes the exit point instead.
+    fn restore(&mut self, selection: Selection) {
+        let len = self.items.list.len();
+        let clamp = |index: Option<usize>| match len {
+            0 => None,
+            len => index.map(|index| index.min(len - 1)),
+        };
+        match self.context {
+            utils::Context::List => {
+                let selected = selection.selected.or(selection.exit_point);
+                self.items.state.select(clamp(selected));
+                self.items.exit_point = selection.exit_point;
             }
-        } else {
-            Ok(())
+            _ => {
+                self.items.state.select(None);
+                self.items.exit_point = selection.selected.or(selection.exit_point);
+            }
+        }
+    }
+
+    /// Adds a change that was just applied to the undo history and saves.
+    fn record(&mut self, change: Change, before: Selection) -> io::Result<()> {
+        let after = self.selection();
+        self.history.record(Entry {
+            change,
+            before,
+            after,
+        });
+        self.save()
+    }
+
+    fn record_move(&mut self, before: Selection) -> io::Result<()> {
+        match (before.selected, self.items.state.selected()) {
+            (Some(a), Some(b)) if a != b => self.record(Change::Swap { a, b }, before),
+            _ => Ok(()),
+        }
+    }
+
+    /// Modifies the selected item, if any, and records it for undo.
+    fn update_selected(&mut self, update: impl FnOnce(&mut Item)) -> io::Result<()> {
+        let before = self.selection();
+        let (index, item) = match before.selected.zip(self.items.selected_mut()) {
+            Some(selected) => selected,
+            None => return Ok(()),
+        };
+        let previous = item.clone();
+        update(item);
+        if *item == previous {
+            return Ok(());
+        }
+        item.touch();
+        let change = Change::Update {
+            index,
+            before: previous,
+            after: item.clone(),
+        };
+        self.record(change, before)
+    }
+
+    /// Opens a prompt on top of the current context, which keeps its selection.
+    /// Editing prompts need a selected item and are ignored without one.
+    fn open_prompt(&mut self, kind: utils::input::Kind) -> io::Result<()> {
+        use utils::input::Kind;
+        if self.context == utils::Context::Input {
+            return Ok(());
+        }
+        let prompt = match kind {
+            Kind::New => input::Prompt::new(self.context),
+            Kind::Rename | Kind::Describe => match self.items.selected_mut() {
+                Some(item) => input::Prompt::with_values(
+                    kind,
+                    self.context,
+                    item.title.clone(),
+                    item.description.clone(),
+                ),
+                None => return Ok(()),
+            },
+        };
+        self.prompt = Some(prompt);
+        self.context = utils::Context::Input;
+        Ok(())
+    }
+
+    fn close_prompt(&mut self) -> io::Result<()> {
+        if let Some(prompt) = self.prompt.take() {
+            self.context = prompt.origin;
+        }
+        Ok(())
+    }
+
+    /// The selected event, if it is still logged and shown.
+    pub fn selected_event(&self) -> Option<&events::Event> {
+        let id = self.event_cursor?;
+        self.events
+            .iter()
+            .find(|event| event.id == id && self.filter.matches(event))
+    }
+
+    fn shown_event_ids(&self) -> Vec<u64> {
+        self.events
+            .iter()
+            .filter(|event| self.filter.matches(event))
+            .map(|event| event.id)
+            .collect()
+    }
+
+    /// Moves the event selection. Up from no selection picks the newest event;
+    /// down past the newest one drops the selection, to follow new events.
+    fn move_event_cursor(&mut self, up: bool) {
+        let shown = self.shown_event_ids();
+        let position = self
+            .event_cursor
+            .and_then(|id| shown.iter().position(|shown| *shown == id));
+        self.event_cursor = match (position, up) {
+            (None, true) => shown.last().copied(),
+            (None, false) => None,
+            (Some(position), true) => shown.get(position.saturating_sub(1)).copied(),
+            (Some(position), false) => shown.get(position + 1).copied(),
+        };
+    }
+
+    fn set_event_status(&mut self, status: events::Status) {
+        let id = match self.selected_event() {
+            Some(event) => event.id,
+            None => return,
+        };
+        if let Some(event) = self.events.get_mut(id) {
+            event.status = status;
         }
     }
 
+    /// Writes the events shown to a new file, and logs where it went.
+    fn export_events(&mut self, format: export::Format) {
+        let shown = self
+            .events
+            .iter()
+            .filter(|event| self.filter.matches(event))
+            .collect::<Vec<&events::Event>>();
+        let count = shown.len();
+        let result = match &self.export_dir {
+            Some(dir) => Ok(dir.clone()),
+            None => paths::data_dir().map(|dir| dir.join("exports")),
+        }
+        .and_then(|dir| export::to_new_file(&dir, events::now_millis(), shown, format));
+        let event = match result {
+            Ok(path) => events::Event::new(
+                events::Severity::Info,
+                format!("exported {} events to {}", count, path.display()),
+            ),
+            Err(err) => events::Event::new(
+                events::Severity::Error,
+                format!("cannot export events: {}", err),
+            ),
+        };
+        self.push_event(event.with_source("cotermi"));
+    }
+
+    fn update_events(&mut self, operation: &utils::events::Operation) -> io::Result<()> {
+        use utils::events::Operation;
+        match operation {
+            Operation::Up => self.move_event_cursor(true),
+            Operation::Down => self.move_event_cursor(false),
+            Operation::Acknowledge => self.set_event_status(events::Status::Acknowledged),
+            Operation::Snooze => self.set_event_status(events::Status::Snoozed {
+                until: events::now_millis() + SNOOZE,
+            }),
+            Operation::Resolve => self.set_event_status(events::Status::Resolved),
+            Operation::AcknowledgeAll => {
+                let now = events::now_millis();
+                for id in self.shown_event_ids() {
+                    match self.events.get_mut(id) {
+                        Some(event) if event.is_outstanding(now) => {
+                            event.status = events::Status::Acknowledged
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            Operation::ToggleOutstanding => self.filter.toggle_outstanding(),
+            Operation::Export(format) => self.export_events(*format),
+            Operation::ToggleStats => self.show_stats = !self.show_stats,
+            Operation::Toggle(severity) => self.filter.toggle(*severity),
+            Operation::RaiseThreshold => self.filter.raise_threshold(),
+            Operation::LowerThreshold => self.filter.lower_threshold(),
+            Operation::ToggleRegex => self.filter.toggle_regex(),
+            Operation::ClearFilter => self.filter.clear(),
+            Operation::Search => self.open_search(),
+        }
+        Ok(())
+    }
+
+    /// Starts editing the events query from the one currently filtered by.
+    fn open_search(&mut self) {
+        if self.context == utils::Context::Input {
+            return;
+        }
+        let query = self.filter.query().to_string();
+        self.search = Some(input::Search::new(self.context, query));
+        self.context = utils::Context::Input;
+    }
+
+    fn close_search(&mut self) {
+        if let Some(search) = self.search.take() {
+            self.context = search.origin;
+        }
+    }
+
+    /// Queues `key`. Which action it stands for depends on the context once
+    /// the signals before it were processed, so it is usually looked up then;
+    /// only a key that is top-level either way can jump the queue.
+    pub fn handle_key(&mut self, key: &KeyEvent) {
+        let settled = self
+            .queue
+            .iter()
+            .all(|signal| signal.stype == utils::SType::TopLevel);
+        let signal = match self
+            .keymap
+            .action(self.context, key)
+            .map(utils::Signal::new)
+        {
+            Some(signal) if settled && signal.stype == utils::SType::TopLevel => signal,
+            _ => utils::Signal::new(utils::Action::Key(*key)),
+        };
+        self.enqueue(signal);
+    }
+
+    fn handle_message(&mut self, message: event_loop::Message) -> io::Result<()> {
+        use event_loop::Message;
+        match message {
+            Message::Input(Event::Key(key)) => self.handle_key(&key),
+            Message::Input(Event::Resize(columns, rows)) => {
+                self.enqueue(utils::Signal::new(utils::Action::Resize(columns, rows)))
+            }
+            Message::Input(_) => {}
+            Message::Tick => self.on_tick()?,
+            Message::Signal(signal) => self.enqueue(signal),
+            Message::Event(event) => self.push_event(event),
+            Message::Failed(err) => return Err(err),
+        }
+        Ok(())
+    }
+
+    /// Renders one frame of the app, whether or not it is dirty.
+    pub fn draw(&mut self, terminal: &mut Terminal<B>) -> io::Result<()> {
+        terminal.draw(|f| self.ui(f))?;
+        self.dirty = false;
+        Ok(())
+    }
+
     fn on_tick(&mut self) -> Result<(), io::Error> {
-        let event = self.events.remove(0);
-        self.events.push(event);
+        // Everything sent since the last tick, though more ticks that piled
+        // up meanwhile add nothing
+        while let Ok(message) = self.inbox.try_recv() {
+            if !matches!(message, event_loop::Message::Tick) {
+                self.handle_message(message)?;
+            }
+        }
+        self.process_signals()?;
+        self.poll_tails();
+        self.poll_commands();
+        // Time alone changes the sparkline, ends flashes and snoozes
+        let second = events::now_millis() / 1_000;
+        if second != self.clock {
+            self.clock = second;
+            let flash_ended = self.flash_until.is_some() && !self.flashing();
+            if flash_ended {
+                self.flash_until = None;
+            }
+            self.dirty |= self.stats_drawn
+                || flash_ended
+                || self
+                    .events
+                    .iter()
+                    .any(|event| matches!(event.status, events::Status::Snoozed { .. }));
+        }
+        Ok(())
+    }
+
+    fn control_commands(&mut self, operation: &utils::command::Operation) -> io::Result<()> {
+        use utils::command::Operation;
+        let mut commands = std::mem::take(&mut self.commands);
+        for command in &mut commands {
+            match operation {
+                Operation::Restart => match command.restart() {
+                    Ok(()) => {
+                        let message = format!("restarted {}", command.line());
+                        let event = events::Event::new(events::Severity::Info, message);
+                        self.push_event(event.with_source(command.name()));
+                    }
+                    Err(err) => self.command_failed(command, "restart", err),
+                },
+                Operation::Kill => {
+                    if let Err(err) = command.kill() {
+                        self.command_failed(command, "kill", err);
+                    }
+                }
+            }
+        }
+        self.commands = commands;
         Ok(())
     }
 
+    fn command_failed(&mut self, command: &command::Command, verb: &str, err: io::Error) {
+        let message = format!("cannot {} {}: {}", verb, command.line(), err);
+        let event = events::Event::new(events::Severity::Error, message);
+        self.push_event(event.with_source("cotermi"));
+    }
+
+    /// Adds the output of running commands, and their exit status once they
+    /// ended: a failure is an error, being killed a warning.
+    fn poll_commands(&mut self) {
+        use command::{Output, Stream};
+        let mut commands = std::mem::take(&mut self.commands);
+        for command in &mut commands {
+            for output in command.poll() {
+                let event = match output {
+                    Output::Line(Stream::Stdout, line) => self.parser.parse(&line),
+                    Output::Line(Stream::Stderr, line) => {
+                        self.parser.parse_or(&line, events::Severity::Warning)
+                    }
+                    Output::Exited(status) => {
+                        let severity = match (status.success(), status.code()) {
+                            (true, _) => events::Severity::Info,
+                            (false, Some(_)) => events::Severity::Error,
+                            (false, None) => events::Severity::Warning,
+                        };
+                        let message = format!("{} ended with {}", command.line(), status);
+                        events::Event::new(severity, message)
+                    }
+                };
+                self.push_event(event.with_source(command.name()));
+            }
+        }
+        self.commands = commands;
+    }
+
+    /// Adds the lines written to tailed files since the last tick. Read errors
+    /// become events themselves rather than stopping the app.
+    fn poll_tails(&mut self) {
+        let mut tails = std::mem::take(&mut self.tails);
+        for tail in &mut tails {
+            let source = tail.name();
+            match tail.poll() {
+                Ok(lines) => {
+                    for line in lines {
+                        let event = self.parser.parse(&line);
+                        self.push_event(event.with_source(source.clone()));
+                    }
+                }
+                Err(err) => {
+                    let message = format!("cannot read {}: {}", tail.path().display(), err);
+                    let event = events::Event::new(events::Severity::Error, message);
+                    self.push_event(event.with_source("cotermi"));
+                }
+            }
+        }
+        self.tails = tails;
+    }
+
     fn ui(&mut self, f: &mut Frame<B>) {
+        let mut area = f.size();
+        self.stats_drawn = self.show_stats && area.height >= STATS_MIN_HEIGHT;
+        if self.stats_drawn {
+            let header = Rect { height: 5, ..area };
+            render_stats(f, header, &self.events, &self.theme);
+            area.y += header.height;
+            area.height -= header.height;
+        }
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .margin(0)
+            .constraints(
+                [
+                    Constraint::Percentage(65),
+                    Constraint::Min(0),
+                    Constraint::Length(1),
+                ]
+                .as_ref(),
+            )
+            .split(area);
         let chunks = Layout::default()
             .direction(Direction::Horizontal)
             .margin(0)
             .constraints([Constraint::Percentage(30), Constraint::Percentage(70)].as_ref())
-            .split(f.size());
+            .split(rows[0]);
 
+        let theme = &self.theme;
         let items = self
             .items
             .list
             .iter()
             .map(|item| {
-                let mut lines = vec![Spans::from(item.0)];
-                for _ in 0..item.1 {
-                    lines.push(Spans::from(Span::styled(
-                        "Lorem ipsum dolor sit amet, consectetur adipiscing elit.",
-                        Style::default().add_modifier(Modifier::ITALIC),
-                    )));
+                let mut lines = vec![Spans::from(item.title.as_str())];
+                for line in &item.body {
+                    lines.push(Spans::from(Span::styled(line.as_str(), theme.body)));
                 }
-                ListItem::new(lines).style(Style::default().fg(Color::White))
+                ListItem::new(lines).style(theme.item)
             })
             .collect::<Vec<ListItem>>();
-        let item_base_style = Style::default().fg(Color::White);
-        let border_style = match &self.items.state.selected() {
-            Some(_) => Style::default()
-                .fg(Color::Red)
-                .bg(Color::Black)
-                .add_modifier(Modifier::RAPID_BLINK),
-            None => Style::default()
-                .fg(Color::LightRed)
-                .bg(Color::DarkGray)
-                .remove_modifier(Modifier::RAPID_BLINK),
+        let border_style = match self.context {
+            utils::Context::List => theme.focused_border,
+            _ => theme.border,
         };
         let list = List::new(items)
             .block(
                 Block::default()
                     .title("List")
                     .borders(Borders::ALL)
-                    .border_style(Style::default().fg(Color::White).bg(Color::Cyan)),
+                    .border_style(border_style),
             )
-            .style(Style::default().fg(Color::Black).bg(Color::Reset))
-            .highlight_style(Style::default().fg(Color::White).bg(Color::LightCyan))
+            .highlight_style(theme.highlight)
             .highlight_symbol(">>");
         f.render_stateful_widget(list, chunks[0], &mut self.items.state);
 
-        let block = Block::default().title("Block 2").borders(Borders::ALL);
-        f.render_widget(block, chunks[1]);
+        self.detail.show(self.focused_item().map(|item| item.id));
+        let focused = self.context == utils::Context::Detail;
+        render_detail(
+            f,
+            chunks[1],
+            self.items.focused(),
+            &mut self.detail,
+            &self.theme,
+            focused,
+        );
+
+        let focused = self.context == utils::Context::Events || self.search.is_some();
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(match focused {
+                true => BorderType::Thick,
+                false => BorderType::Plain,
+            })
+            .border_style(match (self.flashing(), focused) {
+                (true, _) => self.theme.alert,
+                (false, true) => self.theme.focused_border,
+                (false, false) => self.theme.border,
+            });
+        let view = EventsView {
+            log: &self.events,
+            filter: &self.filter,
+            search: self.search.as_ref(),
+            selected: self.event_cursor,
+        };
+        render_events(f, rows[1], view, &self.theme, block);
+        render_status(f, rows[2], &self.alerts, &self.events, &self.theme);
+
+        if let Some(prompt) = &self.prompt {
+            render_prompt(f, prompt, &self.theme);
+        }
     }
 }
 
-pub fn ui<B: Backend>(f: &mut Frame<B>, app: &mut App<B>)
-where
-    B: io::Write,
-{
-    let chunks = Layout::default()
+pub fn ui<B: Backend>(f: &mut Frame<B>, app: &mut App<B>) {
+    app.ui(f)
+}
+
+/// A sparkline of how many events arrived over the last [`RATE_WINDOW`],
+/// in as short spans as fit, next to a bar chart of the events kept per
+/// severity.
+///
+/// [`RATE_WINDOW`]: events::RATE_WINDOW
+fn render_stats<B: Backend>(f: &mut Frame<B>, area: Rect, log: &events::EventLog, theme: &Theme) {
+    let counts = log.counts();
+    // Bars three wide, each followed by a gap, between the borders
+    let chart_width = counts.len() as u16 * 4 + 2;
+    let columns = Layout::default()
         .direction(Direction::Horizontal)
-        .margin(0)
-        .constraints([Constraint::Percentage(30), Constraint::Percentage(70)].as_ref())
-        .split(f.size());
+        .constraints([Constraint::Min(0), Constraint::Length(chart_width)].as_ref())
+        .split(area);
 
-    let items = app
-        .items
-        .list
+    let width = columns[0].width.saturating_sub(2).max(1) as usize;
+    let bucket = events::RATE_WINDOW.div_ceil(width);
+    let buckets = events::RATE_WINDOW.div_ceil(bucket);
+    let rate = log.rate(events::now_millis(), buckets, bucket as u64);
+    let peak = rate.iter().max().copied().unwrap_or(0);
+    let sparkline = Sparkline::default()
+        .block(
+            Block::default()
+                .title(format!(
+                    "Events per {}s, last {}m (peak {})",
+                    bucket,
+                    events::RATE_WINDOW / 60,
+                    peak
+                ))
+                .borders(Borders::ALL)
+                .border_style(theme.border),
+        )
+        .data(&rate)
+        .style(theme.item);
+    f.render_widget(sparkline, columns[0]);
+
+    let labels = counts.map(|(severity, count)| (&severity.name()[..3], count));
+    let chart = BarChart::default()
+        .block(
+            Block::default()
+                .title("Severity")
+                .borders(Borders::ALL)
+                .border_style(theme.border),
+        )
+        .data(&labels)
+        .bar_width(3)
+        .bar_gap(1)
+        .bar_style(theme.item)
+        .value_style(theme.highlight)
+        .label_style(theme.meta);
+    f.render_widget(chart, columns[1]);
+}
+
+/// What the events pane shows, gathered for [`render_events`].
+struct EventsView<'a> {
+    log: &'a events::EventLog,
+    filter: &'a events::Filter,
+    search: Option<&'a input::Search>,
+    selected: Option<u64>,
+}
+
+fn render_events<B: Backend>(
+    f: &mut Frame<B>,
+    area: Rect,
+    view: EventsView,
+    theme: &Theme,
+    block: Block,
+) {
+    let EventsView {
+        log,
+        filter,
+        search,
+        selected,
+    } = view;
+    let shown = log
         .iter()
-        .map(|item| {
-            let mut lines = vec![Spans::from(item.0)];
-            for _ in 0..item.1 {
-                lines.push(Spans::from(Span::styled(
-                    "Lorem ipsum dolor sit amet, consectetur adipiscing elit.",
-                    Style::default().add_modifier(Modifier::ITALIC),
-                )));
+        .filter(|event| filter.matches(event))
+        .collect::<Vec<&events::Event>>();
+    let mut title = match filter.is_active() {
+        true => format!("Events ({}/{}", shown.len(), log.len()),
+        false => format!("Events ({}", log.len()),
+    };
+    if log.dropped() > 0 {
+        title.push_str(&format!(", {} dropped", log.dropped()));
+    }
+    title.push(')');
+    if filter.is_active() {
+        title.push_str(&format!(" [{}]", filter));
+    }
+    let block = block.title(title);
+    // An open search takes the last row
+    let mut visible = area.height.saturating_sub(2) as usize;
+    if search.is_some() {
+        visible = visible.saturating_sub(1);
+    }
+    // Newest events at the bottom, scrolled back far enough to show the
+    // selected one
+    let mut skip = shown.len().saturating_sub(visible);
+    if let Some(position) = selected.and_then(|id| shown.iter().position(|event| event.id == id)) {
+        skip = skip.min(position);
+    }
+    let mut lines = shown
+        .into_iter()
+        .skip(skip)
+        .take(visible)
+        .map(|event| {
+            let severity = theme.severity.get(event.severity);
+            let mut spans = vec![
+                Span::styled(events::format_time(event.timestamp), theme.meta),
+                Span::raw(" "),
+                Span::styled(format!("{:<8}", event.severity.name()), severity),
+                Span::raw(" "),
+            ];
+            if let Some(source) = &event.source {
+                spans.push(Span::styled(format!("[{}] ", source), theme.meta));
+            }
+            if let Some(label) = &event.label {
+                spans.push(Span::styled(format!("{}: ", label), severity));
+            }
+            if event.status != events::Status::Open {
+                spans.push(Span::styled(
+                    format!("({}) ", event.status.name()),
+                    theme.meta,
+                ));
+            }
+            let message = match event.status {
+                events::Status::Resolved => theme.meta,
+                _ => Style::default(),
+            };
+            spans.push(Span::styled(event.message.as_str(), message));
+            for (name, value) in &event.fields {
+                spans.push(Span::styled(format!(" {}={}", name, value), theme.meta));
+            }
+            let item = ListItem::new(Spans::from(spans));
+            match Some(event.id) == selected {
+                true => item.style(theme.highlight),
+                false => item,
             }
-            ListItem::new(lines).style(Style::default().fg(Color::Black).bg(Color::White))
         })
         .collect::<Vec<ListItem>>();
-    let item_base_style = Style::default().fg(Color::White);
-    let border_style = match &app.items.state.selected() {
-        Some(_) => Style::default()
-            .fg(Color::Red)
-            .bg(Color::Black)
-            .add_modifier(Modifier::RAPID_BLINK),
-        None => Style::default()
-            .fg(Color::LightRed)
-            .bg(Color::DarkGray)
-            .remove_modifier(Modifier::RAPID_BLINK),
+    let row = lines.len() as u16;
+    let prefix = match filter.is_regex() {
+        true => "Regex: ",
+        false => "Search: ",
     };
-    let list = List::new(items)
-        .block(
-            Block::default()
-                .title("List")
-                .borders(Borders::ALL)
-                .border_style(border_style),
-        )
-        .style(Style::default().fg(Color::White))
-        .highlight_style(item_base_style.add_modifier(Modifier::ITALIC))
-        .highlight_symbol(">>");
-    f.render_stateful_widget(list, chunks[0], &mut app.items.state);
+    if let Some(search) = search {
+        lines.push(ListItem::new(Spans::from(vec![
+            Span::styled(prefix, theme.title),
+            Span::raw(search.query()),
+        ])));
+    }
+    f.render_widget(List::new(lines).block(block), area);
+
+    if let Some(search) = search {
+        let column = (prefix.len() + search.line.cursor()) as u16;
+        f.set_cursor(
+            (area.x + 1 + column).min(area.right().saturating_sub(2)),
+            area.y + 1 + row,
+        );
+    }
+}
+
+/// A single line with the number of alerts by label, and of outstanding
+/// events by severity.
+fn render_status<B: Backend>(
+    f: &mut Frame<B>,
+    area: Rect,
+    alerts: &BTreeMap<String, usize>,
+    log: &events::EventLog,
+    theme: &Theme,
+) {
+    let total = alerts.values().sum::<usize>();
+    let mut spans = vec![Span::styled(
+        format!(" {} alert{}", total, if total == 1 { "" } else { "s" }),
+        match total {
+            0 => theme.meta,
+            _ => theme.title,
+        },
+    )];
+    if total > 0 {
+        let labels = alerts
+            .iter()
+            .map(|(label, count)| format!("{} {}", label, count))
+            .collect::<Vec<String>>();
+        spans.push(Span::styled(format!(": {}", labels.join(", ")), theme.meta));
+    }
+    let now = events::now_millis();
+    let mut outstanding = BTreeMap::<events::Severity, usize>::new();
+    for event in log.iter().filter(|event| event.is_outstanding(now)) {
+        *outstanding.entry(event.severity).or_default() += 1;
+    }
+    if !outstanding.is_empty() {
+        spans.push(Span::styled(" | outstanding:", theme.title));
+        for (severity, count) in outstanding.iter().rev() {
+            spans.push(Span::raw(" "));
+            spans.push(Span::styled(
+                format!("{} {}", severity, count),
+                theme.severity.get(*severity),
+            ));
+        }
+    }
+    f.render_widget(Paragraph::new(Spans::from(spans)), area);
+}
+
+fn render_detail<B: Backend>(
+    f: &mut Frame<B>,
+    area: Rect,
+    item: Option<&Item>,
+    detail: &mut DetailState,
+    theme: &Theme,
+    focused: bool,
+) {
+    let border_style = match focused {
+        true => theme.focused_border,
+        false => theme.border,
+    };
+    let block = Block::default()
+        .title("Details")
+        .borders(Borders::ALL)
+        .border_type(match focused {
+            true => BorderType::Thick,
+            false => BorderType::Plain,
+        })
+        .border_style(border_style);
+    let item = match item {
+        Some(item) => item,
+        None => {
+            let empty = Paragraph::new(Span::styled("No item selected", theme.meta));
+            f.render_widget(empty.block(block), area);
+            return;
+        }
+    };
+    let label_style = theme.title;
+    let meta_style = theme.meta;
+    let mut lines = vec![
+        Spans::from(Span::styled(item.title.as_str(), label_style)),
+        Spans::from(match item.description.is_empty() {
+            true => Span::styled("No description", meta_style),
+            false => Span::raw(item.description.as_str()),
+        }),
+        Spans::default(),
+        Spans::from(Span::styled(format!("id: {}", item.id), meta_style)),
+        Spans::from(Span::styled(
+            format!("created: {}", item::format_timestamp(item.created)),
+            meta_style,
+        )),
+        Spans::from(Span::styled(
+            format!("updated: {}", item::format_timestamp(item.updated)),
+            meta_style,
+        )),
+        Spans::default(),
+    ];
+    for line in &item.body {
+        lines.push(Spans::from(Span::styled(line.as_str(), theme.body)));
+    }
+    // Measure the wrapped text so scrolling stops at the last line
+    let inner_width = area.width.saturating_sub(2).max(1) as usize;
+    let rows = lines
+        .iter()
+        .map(|line| match line.width() {
+            0 => 1,
+            width => width.div_ceil(inner_width),
+        })
+        .sum::<usize>();
+    detail.page = area.height.saturating_sub(2);
+    detail.max_scroll = (rows as u16).saturating_sub(detail.page);
+    detail.scroll = detail.scroll.min(detail.max_scroll);
+
+    let paragraph = Paragraph::new(lines)
+        .block(block.title(format!("Details: {}", item.title)))
+        .wrap(Wrap { trim: false })
+        .scroll((detail.scroll, 0));
+    f.render_widget(paragraph, area);
+}
+
+/// A rectangle of `width` by `height` cells centered in `area`, clamped to fit.
+fn centered_rect(width: u16, height: u16, area: Rect) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    )
+}
+
+fn render_prompt<B: Backend>(f: &mut Frame<B>, prompt: &input::Prompt, theme: &Theme) {
+    let fields = prompt.fields();
+    let area = centered_rect(60, fields.len() as u16 + 2, f.size());
+    let label_style = theme.title;
+    let lines = fields
+        .iter()
+        .map(|field| {
+            let line = match field {
+                input::Field::Title => &prompt.title,
+                input::Field::Description => &prompt.description,
+            };
+            let style = match *field == prompt.field {
+                true => label_style.patch(theme.focused_border),
+                false => label_style,
+            };
+            Spans::from(vec![
+                Span::styled(field.label(), style),
+                Span::raw(line.text()),
+            ])
+        })
+        .collect::<Vec<Spans>>();
+    let block = Block::default()
+        .title(format!("{} (Enter: confirm, Esc: cancel)", prompt.title()))
+        .borders(Borders::ALL)
+        .border_style(theme.focused_border);
+    f.render_widget(Clear, area);
+    f.render_widget(Paragraph::new(lines).block(block), area);
 
-    let block = Block::default().title("Block 2").borders(Borders::ALL);
-    f.render_widget(block, chunks[1]);
+    let row = fields
+        .iter()
+        .position(|field| field == &prompt.field)
+        .unwrap_or_default() as u16;
+    let column = (prompt.field.label().len() + prompt.focused().cursor()) as u16;
+    f.set_cursor(
+        (area.x + 1 + column).min(area.right().saturating_sub(2)),
+        area.y + 1 + row,
+    );
 }
 
 // #[derive(Clone, Copy)]
@@ -438,52 +1490,79 @@ where
 //     }
 // }
 
-pub fn run_app<B: Backend>(
+/// Runs `app` on `terminal` until it quits. The backend also has to take
+/// raw writes, for the bell.
+pub fn run_app<B: Backend + io::Write>(
     terminal: &mut Terminal<B>,
-    app: &'static mut App<B>,
+    app: &mut App<B>,
     tick_rate: Duration,
-) -> io::Result<()>
-where
-    B: io::Write,
-{
-    let mut last_tick = Instant::now();
-    loop {
-        terminal.draw(|f| app.ui(f))?;
-
-        let timeout = tick_rate
-            .checked_sub(last_tick.elapsed())
-            .unwrap_or_else(|| Duration::from_secs(0));
-        if crossterm::event::poll(timeout)? {
-            app.poll(terminal)?;
-            // app.roll(terminal)?;
-            // if let Event::Key(key) = event::read()? {
-            //     if let Some(active_block) = app.state {
-            //         match active_block {
-            //             ActiveBlock::ListBlock => match key.code {
-            //                 KeyCode::Char('q') => return Ok(()),
-            //                 KeyCode::Right => app.items.deselect(),
-            //                 KeyCode::Down => app.items.next(),
-            //                 KeyCode::Up => app.items.prev(),
-            //                 _ => {}
-            //             },
-            //             ActiveBlock::Other => match key.code {
-            //                 KeyCode::Char('q') => return Ok(()),
-            //                 KeyCode::Left => app.items.reselect(),
-            //                 KeyCode::Down => app.items.reselect_next(),
-            //                 KeyCode::Up => app.items.reselect_prev(),
-            //                 _ => {}
-            //             },
-            //             _ => match key.code {
-            //                 KeyCode::Char('q') => return Ok(()),
-            //                 _ => {}
-            //             },
-            //         }
-            //     }
-            // }
-        }
-        if last_tick.elapsed() >= tick_rate {
-            app.on_tick()?;
-            last_tick = Instant::now();
+) -> io::Result<()> {
+    app.threads.spawn_input(app.producer());
+    app.threads.spawn_ticks(app.producer(), tick_rate);
+    let result = run_loop(terminal, app);
+    // Joined even if the loop failed, so no thread outlives the terminal
+    let shutdown = app.threads.shutdown();
+    result.and(shutdown)
+}
+
+fn run_loop<B: Backend + io::Write>(
+    terminal: &mut Terminal<B>,
+    app: &mut App<B>,
+) -> io::Result<()> {
+    let frame = Duration::from_secs(1) / app.max_fps;
+    let mut last_frame = None::<Instant>;
+    while app.running {
+        // A frame that is due too soon after the last one waits for the
+        // next message or the end of the frame, whichever comes first
+        let mut timeout = None;
+        if app.is_dirty() {
+            let wait = last_frame.map_or(Duration::ZERO, |at| frame.saturating_sub(at.elapsed()));
+            if wait.is_zero() {
+                app.draw(terminal)?;
+                last_frame = Some(Instant::now());
+            } else {
+                timeout = Some(wait);
+            }
         }
+        if app.take_bell() {
+            // Through the backend, so it stays in order with what is drawn
+            let backend = terminal.backend_mut();
+            io::Write::write_all(backend, b"\x07")?;
+            Backend::flush(backend)?;
+        }
+        // The app holds a producer itself, so the channel never disconnects
+        let message = match timeout {
+            Some(timeout) => match app.inbox.recv_timeout(timeout) {
+                Ok(message) => message,
+                Err(mpsc::RecvTimeoutError::Timeout) => continue,
+                Err(mpsc::RecvTimeoutError::Disconnected) => break,
+            },
+            None => match app.inbox.recv() {
+                Ok(message) => message,
+                Err(_) => break,
+            },
+        };
+        app.handle_message(message)?;
+        if app.take_suspend() {
+            suspend(terminal)?;
+            app.dirty = true;
+        }
+    }
+    Ok(())
+}
+
+/// Restores the shell's terminal and stops the process like ctrl-z does in
+/// other programs, then takes the terminal back once it is resumed.
+fn suspend<B: Backend>(terminal: &mut Terminal<B>) -> io::Result<()> {
+    #[cfg(unix)]
+    {
+        crate::terminal::release()?;
+        // SAFETY: raise(3) only takes a signal number; it returns on SIGCONT
+        unsafe { libc::raise(libc::SIGTSTP) };
+        crate::terminal::acquire()?;
+        terminal.clear()?;
     }
+    #[cfg(not(unix))]
+    let _ = terminal;
+    Ok(())
 }
@@ -1,54 +1,130 @@
-pub mod ctxt;
-pub mod listop;
-pub mod ls;
-pub mod signal;
-use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode},
-    execute,
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+use cotermi::*;
+use std::{
+    env, fs,
+    io::{self, BufReader, Write},
+    path::PathBuf,
+    process,
 };
-use ocaml_notebook::*;
-use std::{io, thread, time::Duration};
-use tui::{
-    backend::CrosstermBackend,
-    layout::{Constraint, Direction, Layout},
-    widgets::{Block, Borders, Widget},
-    Terminal,
-};
-fn main() -> Result<(), io::Error> {
-    // setup terminal
-    enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
+use tui::{backend::CrosstermBackend, Terminal};
+/// Reports a problem with the arguments or the files they name, before the
+/// terminal is touched.
+fn fail(err: impl std::fmt::Display) -> ! {
+    eprintln!("cotermi: {}", err);
+    process::exit(2);
+}
+
+/// `cotermi export`: parses log files, or stdin without any or for `-`,
+/// like tailed lines, and writes them out as events.
+fn export(args: cli::ExportArgs) -> Result<(), String> {
+    let format = args
+        .format
+        .or_else(|| args.output.as_deref().and_then(export::Format::from_path))
+        .unwrap_or(export::Format::JsonLines);
+    let min_level = args.min_level.unwrap_or(events::Severity::Trace);
+    let config_path = match args.config {
+        // Unlike the default one, a config file asked for has to exist
+        Some(path) => match fs::metadata(&path) {
+            Ok(_) => Some(path),
+            Err(err) => return Err(format!("{}: {}", path.display(), err)),
+        },
+        // Without a config directory there is no config file to read either
+        None => config::Config::default_path().ok(),
+    };
+    let config = match config_path {
+        Some(path) => config::Config::load(&path).map_err(|err| err.to_string())?,
+        None => config::Config::default(),
+    };
+    let mut inputs = args.inputs;
+    if inputs.is_empty() {
+        inputs.push(PathBuf::from("-"));
+    }
 
-    // create app and run it
-    let tick_rate = Duration::from_millis(50);
-    let mut app = App::new();
-    run_app(&mut terminal, &mut app, tick_rate)?;
-    // terminal.draw(|f| {
-    //     let size = f.size();
-    //     let block = Block::default().title("Block").borders(Borders::ALL);
-    //     ui(f);
-    //     // f.render_widget(block, size);
-    // })?;
+    let mut events = vec![];
+    for input in &inputs {
+        let read = match input.to_str() {
+            Some("-") => export::read(io::stdin().lock(), &config.parser, None, min_level),
+            _ => fs::File::open(input).and_then(|file| {
+                let name = input.file_name().map(|name| name.to_string_lossy());
+                export::read(
+                    BufReader::new(file),
+                    &config.parser,
+                    name.as_deref(),
+                    min_level,
+                )
+            }),
+        };
+        events.extend(read.map_err(|err| format!("{}: {}", input.display(), err))?);
+    }
+    // Numbered across all inputs
+    for (id, event) in events.iter_mut().enumerate() {
+        event.id = id as u64;
+    }
+    match args.output {
+        Some(path) => export::to_file(&path, &events, format)
+            .map_err(|err| format!("{}: {}", path.display(), err)),
+        None => {
+            export::write(&mut io::stdout().lock(), &events, format).map_err(|err| err.to_string())
+        }
+    }
+}
+
+fn main() -> Result<(), io::Error> {
+    let args = match cli::parse(env::args_os().skip(1)) {
+        Ok(cli::Invocation::Run(args)) => args,
+        Ok(cli::Invocation::Export(args)) => {
+            if let Err(err) = export(args) {
+                fail(err);
+            }
+            return Ok(());
+        }
+        Ok(cli::Invocation::Help) => {
+            // Fine if stdout is already closed, as with `| head`
+            let _ = writeln!(io::stdout(), "{}", cli::USAGE);
+            return Ok(());
+        }
+        Ok(cli::Invocation::Version) => {
+            let _ = writeln!(io::stdout(), "cotermi {}", env!("CARGO_PKG_VERSION"));
+            return Ok(());
+        }
+        Err(err) => fail(format!(
+            "{}\nTry 'cotermi --help' for more information.",
+            err
+        )),
+    };
+    // load config and persisted items before touching the terminal
+    let config_path = match args.config {
+        // Unlike the default one, a config file asked for has to exist
+        Some(path) => match fs::metadata(&path) {
+            Ok(_) => path,
+            Err(err) => fail(format!("{}: {}", path.display(), err)),
+        },
+        None => config::Config::default_path().unwrap_or_else(|err| fail(err)),
+    };
+    let mut config = config::Config::load(&config_path).unwrap_or_else(|err| fail(err));
+    if let Some(name) = &args.theme {
+        config.set_theme(name).unwrap_or_else(|err| fail(err));
+    }
+    let tick_rate = args.tick_rate.unwrap_or(config.tick_rate);
+    let data_file = match args.data_file {
+        Some(path) => path,
+        None => storage::Storage::default_path().unwrap_or_else(|err| fail(err)),
+    };
+    let storage = storage::Storage::new(&data_file);
+    let app = App::with_storage(storage)
+        .unwrap_or_else(|err| fail(format!("{}: {}", data_file.display(), err)));
+    let mut app = app.with_config(config);
+    for path in args.tails {
+        app = app.with_tail(path);
+    }
+    for line in args.commands {
+        app = app.with_command(line);
+    }
 
-    // thread::sleep(Duration::from_millis(5000));
-    // for _ in 0..10 {
-    //     terminal.show_cursor()?;
-    //     thread::sleep(Duration::from_millis(500));
-    //     terminal.hide_cursor()?;
-    //     thread::sleep(Duration::from_millis(500))
-    // }
+    // setup terminal; it is restored when the guard is dropped, even if the
+    // app fails or panics
+    let _guard = terminal::Guard::new()?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(io::stdout()))?;
 
-    // restore terminal
-    disable_raw_mode()?;
-    execute!(
-        terminal.backend_mut(),
-        LeaveAlternateScreen,
-        DisableMouseCapture
-    )?;
-    terminal.show_cursor()?;
-    Ok(())
+    // run app
+    run_app(&mut terminal, &mut app, tick_rate)
 }
@@ -0,0 +1,453 @@
+//! Turns log lines into [`Event`]s. Every line becomes an event: lines that
+//! do not match the configured format are kept as they are, with a severity
+//! guessed from their words.
+use crate::events::{now_millis, Event, Severity, Status};
+use regex::Regex;
+use serde_json::Value;
+
+/// The formats a line can be parsed as.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    /// Picks JSON, RFC 5424 syslog or logfmt by the shape of each line.
+    Auto,
+    Json,
+    Logfmt,
+    Syslog,
+    /// The user's pattern, see [`Parser::with_pattern`].
+    Regex,
+    Raw,
+}
+
+impl Format {
+    pub const NAMES: &'static [&'static str] =
+        &["auto", "json", "logfmt", "syslog", "regex", "raw"];
+
+    pub fn from_name(name: &str) -> Option<Format> {
+        match name {
+            "auto" => Some(Format::Auto),
+            "json" => Some(Format::Json),
+            "logfmt" => Some(Format::Logfmt),
+            "syslog" => Some(Format::Syslog),
+            "regex" => Some(Format::Regex),
+            "raw" => Some(Format::Raw),
+            _ => None,
+        }
+    }
+}
+
+/// The field names looked up in JSON and logfmt lines, first match wins.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Keys {
+    pub level: Vec<String>,
+    pub message: Vec<String>,
+    pub timestamp: Vec<String>,
+}
+
+impl Default for Keys {
+    fn default() -> Self {
+        let keys = |names: &[&str]| names.iter().map(|name| name.to_string()).collect();
+        Self {
+            level: keys(&["level", "severity", "lvl"]),
+            message: keys(&["message", "msg"]),
+            timestamp: keys(&["timestamp", "time", "ts"]),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Parser {
+    format: Format,
+    keys: Keys,
+    pattern: Option<Regex>,
+}
+
+impl Default for Parser {
+    fn default() -> Self {
+        Self::new(Format::Auto)
+    }
+}
+
+/// The parts of a line that make up an event, before falling back to
+/// defaults for whatever is missing.
+#[derive(Default)]
+struct Fields {
+    level: Option<Severity>,
+    message: Option<String>,
+    timestamp: Option<u64>,
+    /// Every other key and its value, as the event's fields.
+    extra: Vec<(String, String)>,
+}
+
+impl Parser {
+    pub fn new(format: Format) -> Self {
+        Self {
+            format,
+            keys: Keys::default(),
+            pattern: None,
+        }
+    }
+    pub fn with_keys(mut self, keys: Keys) -> Self {
+        self.keys = keys;
+        self
+    }
+    /// A regex whose named captures `level`, `message` and `timestamp` are
+    /// used; without a `message` capture the whole line is the message. In
+    /// [`Format::Auto`] it is tried before the built-in formats.
+    pub fn with_pattern(mut self, pattern: Regex) -> Self {
+        self.pattern = Some(pattern);
+        self
+    }
+    pub fn format(&self) -> Format {
+        self.format
+    }
+    /// Parses `line`, falling back to a raw event if it does not match.
+    pub fn parse(&self, line: &str) -> Event {
+        self.parse_or(line, Severity::Info)
+    }
+    /// Like [`Parser::parse`], with `fallback` as the severity of lines that
+    /// neither carry nor mention a level.
+    pub fn parse_or(&self, line: &str, fallback: Severity) -> Event {
+        let fields = match self.format {
+            Format::Auto => self
+                .regex(line)
+                .or_else(|| match line.trim_start().starts_with('{') {
+                    true => self.json(line),
+                    false => None,
+                })
+                .or_else(|| syslog(line))
+                .or_else(|| self.logfmt(line).filter(|fields| fields.message.is_some())),
+            Format::Json => self.json(line),
+            Format::Logfmt => self.logfmt(line),
+            Format::Syslog => syslog(line),
+            Format::Regex => self.regex(line),
+            Format::Raw => None,
+        };
+        let fields = fields.unwrap_or_default();
+        Event {
+            id: 0,
+            timestamp: fields.timestamp.unwrap_or_else(now_millis),
+            severity: fields
+                .level
+                .or_else(|| infer_severity(line))
+                .unwrap_or(fallback),
+            message: fields.message.unwrap_or_else(|| line.to_string()),
+            source: None,
+            label: None,
+            status: Status::Open,
+            fields: fields.extra,
+        }
+    }
+    fn json(&self, line: &str) -> Option<Fields> {
+        let object = match serde_json::from_str::<Value>(line).ok()? {
+            Value::Object(object) => object,
+            _ => return None,
+        };
+        let find = |keys: &[String]| keys.iter().find_map(|key| object.get(key));
+        let text = |value: &Value| match value {
+            Value::String(text) => text.clone(),
+            value => value.to_string(),
+        };
+        let used = [&self.keys.level, &self.keys.message, &self.keys.timestamp]
+            .map(|keys| keys.iter().find(|key| object.contains_key(*key)));
+        let extra = object
+            .iter()
+            .filter(|(key, _)| !used.contains(&Some(key)))
+            .map(|(key, value)| (key.clone(), text(value)))
+            .collect();
+        Some(Fields {
+            level: find(&self.keys.level).and_then(|value| match value {
+                Value::Number(number) => number.as_u64().and_then(numeric_level),
+                value => Severity::from_name(&text(value)),
+            }),
+            message: find(&self.keys.message).map(text),
+            timestamp: find(&self.keys.timestamp).and_then(|value| match value {
+                Value::Number(number) => number.as_f64().map(epoch_millis),
+                value => parse_timestamp(&text(value)),
+            }),
+            extra,
+        })
+    }
+    fn logfmt(&self, line: &str) -> Option<Fields> {
+        let pairs = logfmt_pairs(line)?;
+        let find = |keys: &[String]| {
+            keys.iter()
+                .find_map(|key| pairs.iter().position(|(name, _)| name == key))
+        };
+        let used = [
+            find(&self.keys.level),
+            find(&self.keys.message),
+            find(&self.keys.timestamp),
+        ];
+        let value = |index: Option<usize>| index.map(|index| pairs[index].1.as_str());
+        Some(Fields {
+            level: value(used[0]).and_then(Severity::from_name),
+            message: value(used[1]).map(str::to_string),
+            timestamp: value(used[2]).and_then(parse_timestamp),
+            extra: pairs
+                .iter()
+                .enumerate()
+                .filter(|(index, _)| !used.contains(&Some(*index)))
+                .map(|(_, pair)| pair.clone())
+                .collect(),
+        })
+    }
+    fn regex(&self, line: &str) -> Option<Fields> {
+        let captures = self.pattern.as_ref()?.captures(line)?;
+        let capture = |name| captures.name(name).map(|capture| capture.as_str());
+        Some(Fields {
+            level: capture("level").and_then(Severity::from_name),
+            message: capture("message").map(str::to_string),
+            timestamp: capture("timestamp").and_then(parse_timestamp),
+            extra: vec![],
+        })
+    }
+}
+
+/// Parses an RFC 5424 line:
+/// `<PRI>1 TIMESTAMP HOSTNAME APP-NAME PROCID MSGID STRUCTURED-DATA MSG`.
+fn syslog(line: &str) -> Option<Fields> {
+    let rest = line.strip_prefix('<')?;
+    let (priority, rest) = rest.split_once('>')?;
+    let priority = priority.parse::<u8>().ok().filter(|pri| *pri < 192)?;
+    let mut header = rest.splitn(6, ' ');
+    let (version, timestamp, _host, app, _procid) = (
+        header.next()?,
+        header.next()?,
+        header.next()?,
+        header.next()?,
+        header.next()?,
+    );
+    if version != "1" {
+        return None;
+    }
+    let (_msgid, rest) = header.next()?.split_once(' ').unwrap_or(("-", ""));
+    let message = skip_structured_data(rest)?;
+    let message = message.strip_prefix('\u{feff}').unwrap_or(message);
+    Some(Fields {
+        level: Some(syslog_severity(priority % 8)),
+        message: Some(match app {
+            "-" => message.to_string(),
+            app => format!("{}: {}", app, message),
+        }),
+        timestamp: match timestamp {
+            "-" => None,
+            timestamp => parse_timestamp(timestamp),
+        },
+        extra: vec![],
+    })
+}
+
+/// Skips the `-` or `[id key="value"]...` structured data, returning the
+/// message after it.
+fn skip_structured_data(rest: &str) -> Option<&str> {
+    if let Some(message) = rest.strip_prefix('-') {
+        return Some(message.strip_prefix(' ').unwrap_or(message));
+    }
+    let mut in_element = false;
+    let (mut quoted, mut escaped) = (false, false);
+    for (index, c) in rest.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if quoted => escaped = true,
+            '"' if in_element => quoted = !quoted,
+            '[' if !quoted => in_element = true,
+            ']' if !quoted => in_element = false,
+            ' ' if !in_element => return Some(&rest[index + 1..]),
+            _ if !in_element => return None,
+            _ => {}
+        }
+    }
+    match in_element {
+        true => None,
+        false => Some(""),
+    }
+}
+
+fn syslog_severity(severity: u8) -> Severity {
+    match severity {
+        0..=2 => Severity::Critical,
+        3 => Severity::Error,
+        4 => Severity::Warning,
+        5 | 6 => Severity::Info,
+        _ => Severity::Debug,
+    }
+}
+
+/// Numeric levels as used by bunyan and pino: 10 is trace, up to 60 for fatal.
+fn numeric_level(level: u64) -> Option<Severity> {
+    match level {
+        0..=10 => Some(Severity::Trace),
+        11..=20 => Some(Severity::Debug),
+        21..=30 => Some(Severity::Info),
+        31..=40 => Some(Severity::Warning),
+        41..=50 => Some(Severity::Error),
+        51..=60 => Some(Severity::Critical),
+        _ => None,
+    }
+}
+
+/// Splits `key=value key2="quoted value" flag` into pairs. Lines without a
+/// single `key=value` pair are not logfmt.
+fn logfmt_pairs(line: &str) -> Option<Vec<(String, String)>> {
+    let mut pairs = vec![];
+    let mut chars = line.trim().chars().peekable();
+    let mut has_value = false;
+    while chars.peek().is_some() {
+        let mut key = String::new();
+        let mut assigned = false;
+        while let Some(c) = chars.next_if(|c| *c != ' ') {
+            if c == '=' {
+                assigned = true;
+                break;
+            }
+            key.push(c);
+        }
+        if key.is_empty() || key.contains('"') {
+            return None;
+        }
+        let mut value = String::new();
+        if assigned && chars.next_if_eq(&'"').is_some() {
+            let mut escaped = false;
+            for c in chars.by_ref() {
+                match c {
+                    _ if escaped => {
+                        value.push(c);
+                        escaped = false;
+                    }
+                    '\\' => escaped = true,
+                    '"' => break,
+                    c => value.push(c),
+                }
+            }
+        } else if assigned {
+            while let Some(c) = chars.next_if(|c| *c != ' ') {
+                value.push(c);
+            }
+        }
+        has_value |= assigned;
+        while chars.next_if_eq(&' ').is_some() {}
+        pairs.push((key, value));
+    }
+    match has_value {
+        true => Some(pairs),
+        false => None,
+    }
+}
+
+/// Level names that are also everyday words, such as "don't panic", so they
+/// only count when shaped like a level.
+const WORDY_LEVELS: &[&str] = &[
+    "information",
+    "notice",
+    "err",
+    "alert",
+    "emerg",
+    "emergency",
+    "panic",
+];
+
+/// Guesses the severity of an unstructured line from its first word shaped
+/// like a level: in capitals as in `ERROR`, bracketed as in `[warn]`, or
+/// followed by a colon as in `fatal:`. The leading word counts as is, unless
+/// it is one of [`WORDY_LEVELS`]. Words joined by `_` or `::`, like
+/// `rules::error_path`, are names rather than levels.
+pub fn infer_severity(line: &str) -> Option<Severity> {
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+    let mut words = vec![];
+    let mut start = None;
+    for (index, c) in line.char_indices().chain([(line.len(), ' ')]) {
+        match (start, is_word(c)) {
+            (None, true) => start = Some(index),
+            (Some(from), false) => {
+                words.push((from, index));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    let mut leading = true;
+    words.into_iter().find_map(|(from, to)| {
+        let word = &line[from..to];
+        if !word.bytes().all(|b| b.is_ascii_alphabetic()) {
+            return None;
+        }
+        let leading = std::mem::replace(&mut leading, false);
+        let severity = Severity::from_name(word)?;
+        let (before, after) = (&line[..from], &line[to..]);
+        let shaped = (word.len() >= 3 && word.bytes().all(|b| b.is_ascii_uppercase()))
+            || (before.ends_with('[') && after.starts_with(']'))
+            || (after.starts_with(':') && !after.starts_with("::") && !before.ends_with(':'));
+        let wordy = WORDY_LEVELS.contains(&word.to_ascii_lowercase().as_str());
+        (shaped || (leading && !wordy)).then_some(severity)
+    })
+}
+
+/// Unix timestamps in seconds, or in milliseconds if too large for seconds.
+fn epoch_millis(value: f64) -> u64 {
+    match value {
+        value if value >= 1e11 => value as u64,
+        value => (value * 1_000.0) as u64,
+    }
+}
+
+/// Parses RFC 3339 timestamps such as `2024-05-01T12:00:00.5+02:00`, a space
+/// instead of the `T` included, as well as unix timestamps.
+pub fn parse_timestamp(text: &str) -> Option<u64> {
+    let text = text.trim();
+    if let Ok(value) = text.parse::<f64>() {
+        return Some(epoch_millis(value)).filter(|_| value >= 0.0);
+    }
+    let number = |range: std::ops::Range<usize>| text.get(range)?.parse::<i64>().ok();
+    let bytes = text.as_bytes();
+    let separators = [(4, b'-'), (7, b'-'), (13, b':'), (16, b':')];
+    if bytes.len() < 19
+        || separators.iter().any(|(index, c)| bytes[*index] != *c)
+        || !matches!(bytes[10], b'T' | b't' | b' ')
+    {
+        return None;
+    }
+    let (year, month, day) = (number(0..4)?, number(5..7)?, number(8..10)?);
+    let (hour, minute, second) = (number(11..13)?, number(14..16)?, number(17..19)?);
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) || hour > 23 || minute > 59 {
+        return None;
+    }
+    let mut rest = &text[19..];
+    let mut millis = 0;
+    if let Some(fraction) = rest.strip_prefix('.') {
+        let digits = fraction.bytes().take_while(u8::is_ascii_digit).count();
+        let padded = format!("{:0<3}", &fraction[..digits.min(3)]);
+        millis = padded.parse::<i64>().ok()?;
+        rest = &fraction[digits..];
+    }
+    let offset = match rest {
+        "" | "Z" | "z" => 0,
+        // Checked byte by byte, as a multi-byte char could split any slice
+        offset if offset.len() == 6 && offset.as_bytes()[3] == b':' => {
+            let sign = match offset.as_bytes()[0] {
+                b'+' => 1,
+                b'-' => -1,
+                _ => return None,
+            };
+            let (hours, minutes) = (
+                offset.get(1..3)?.parse::<i64>().ok()?,
+                offset.get(4..6)?.parse::<i64>().ok()?,
+            );
+            sign * (hours * 3_600 + minutes * 60)
+        }
+        _ => return None,
+    };
+    let days = days_from_civil(year, month, day);
+    let secs = days * 86_400 + hour * 3_600 + minute * 60 + second - offset;
+    u64::try_from(secs * 1_000 + millis).ok()
+}
+
+/// Days since the unix epoch, see
+/// http://howardhinnant.github.io/date_algorithms.html
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
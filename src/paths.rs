@@ -0,0 +1,30 @@
+use std::{env, io, path::PathBuf};
+
+/// `$<var>/cotermi`, falling back to `~/<fallback>/cotermi` when unset.
+fn xdg_dir(var: &str, fallback: &[&str]) -> io::Result<PathBuf> {
+    let base = match env::var_os(var) {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => match env::var_os("HOME") {
+            Some(home) => fallback
+                .iter()
+                .fold(PathBuf::from(home), |dir, component| dir.join(component)),
+            None => {
+                return Err(io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("neither {} nor HOME is set", var),
+                ))
+            }
+        },
+    };
+    Ok(base.join("cotermi"))
+}
+
+/// `$XDG_DATA_HOME/cotermi`, falling back to `~/.local/share/cotermi`.
+pub fn data_dir() -> io::Result<PathBuf> {
+    xdg_dir("XDG_DATA_HOME", &[".local", "share"])
+}
+
+/// `$XDG_CONFIG_HOME/cotermi`, falling back to `~/.config/cotermi`.
+pub fn config_dir() -> io::Result<PathBuf> {
+    xdg_dir("XDG_CONFIG_HOME", &[".config"])
+}
@@ -0,0 +1,95 @@
+//! Alert rules, evaluated against every event as it is logged. A matching
+//! rule can escalate the event's severity, label it, and trigger effects
+//! such as flashing the events pane or ringing the terminal bell.
+use crate::events::{Event, Severity};
+use regex::Regex;
+
+/// What the UI does when a rule matches.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Effect {
+    /// Blinks the border of the events pane for a while.
+    Flash,
+    Bell,
+}
+
+impl Effect {
+    pub fn from_name(name: &str) -> Option<Effect> {
+        match name {
+            "flash" => Some(Effect::Flash),
+            "bell" => Some(Effect::Bell),
+            _ => None,
+        }
+    }
+}
+
+/// A rule matches events that satisfy all of its conditions.
+#[derive(Clone, Debug, Default)]
+pub struct Rule {
+    /// Searched for in the message.
+    pub pattern: Option<Regex>,
+    /// The exact source, e.g. the name of a tailed file.
+    pub source: Option<String>,
+    /// The least severity an event needs to match.
+    pub level: Option<Severity>,
+    /// Fields the event needs, each with a value the regex is found in.
+    pub fields: Vec<(String, Regex)>,
+    /// Raises matching events to this severity; never lowers them.
+    pub severity: Option<Severity>,
+    pub label: Option<String>,
+    pub effects: Vec<Effect>,
+}
+
+impl Rule {
+    pub fn matches(&self, event: &Event) -> bool {
+        self.pattern
+            .as_ref()
+            .is_none_or(|pattern| pattern.is_match(&event.message))
+            && self
+                .source
+                .as_ref()
+                .is_none_or(|source| event.source.as_ref() == Some(source))
+            && self.level.is_none_or(|level| event.severity >= level)
+            && self.fields.iter().all(|(name, pattern)| {
+                let mut values = event.fields.iter().filter(|(key, _)| key == name);
+                values.any(|(_, value)| pattern.is_match(value))
+            })
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Rules {
+    rules: Vec<Rule>,
+}
+
+impl Rules {
+    pub fn new(rules: Vec<Rule>) -> Self {
+        Self { rules }
+    }
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+    /// Applies every rule matching `event` to it and returns the effects to
+    /// trigger, or `None` if no rule matched. The first label wins.
+    pub fn apply(&self, event: &mut Event) -> Option<Vec<Effect>> {
+        let mut effects = None::<Vec<Effect>>;
+        // In order, so later rules see the severity earlier ones escalated to
+        for rule in &self.rules {
+            if !rule.matches(event) {
+                continue;
+            }
+            let matched = effects.get_or_insert_with(Vec::new);
+            if let Some(severity) = rule.severity {
+                event.severity = event.severity.max(severity);
+            }
+            if event.label.is_none() {
+                event.label = rule.label.clone();
+            }
+            for effect in &rule.effects {
+                if !matched.contains(effect) {
+                    matched.push(*effect);
+                }
+            }
+        }
+        effects
+    }
+}
@@ -1,6 +0,0 @@
-#[derive(Clone, Copy)]
-pub enum Signal<'a> {
-    Quit,
-    ChCtxt(crate::ctxt::Ctxt),
-    List(crate::list::ListOp<'a>),
-}
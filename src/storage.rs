@@ -0,0 +1,113 @@
+use crate::{item::Item, paths};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// Version written by [`Storage::save`]; older versions are migrated on load.
+pub const FORMAT_VERSION: u32 = 2;
+
+/// Placeholder body line that version 1 files only stored a count of.
+const V1_BODY_LINE: &str = "Lorem ipsum dolor sit amet, consectetur adipiscing elit.";
+
+#[derive(Deserialize)]
+struct Header {
+    version: u32,
+}
+
+#[derive(Deserialize)]
+struct Document {
+    items: Vec<Item>,
+}
+
+#[derive(Serialize)]
+struct DocumentRef<'a> {
+    version: u32,
+    items: &'a [Item],
+}
+
+#[derive(Deserialize)]
+struct DocumentV1 {
+    items: Vec<ItemV1>,
+}
+
+#[derive(Deserialize)]
+struct ItemV1 {
+    title: String,
+    lines: usize,
+}
+
+impl DocumentV1 {
+    fn migrate(self) -> Vec<Item> {
+        self.items
+            .into_iter()
+            .zip(0..)
+            .map(|(item, id)| {
+                Item::new(id, item.title, "").with_body(vec![V1_BODY_LINE.to_string(); item.lines])
+            })
+            .collect()
+    }
+}
+
+/// Reads the list items from, and writes them to, a JSON file on disk.
+pub struct Storage {
+    path: PathBuf,
+}
+
+impl Storage {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+    /// `items.json` in the [data directory](crate::paths::data_dir).
+    pub fn default_path() -> io::Result<PathBuf> {
+        Ok(paths::data_dir()?.join("items.json"))
+    }
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+    /// Loads the stored items, or an empty list if the file does not exist yet.
+    pub fn load(&self) -> io::Result<Vec<Item>> {
+        let contents = match fs::read_to_string(&self.path) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(err) => return Err(err),
+        };
+        let header: Header = self.parse(&contents)?;
+        match header.version {
+            1 => Ok(self.parse::<DocumentV1>(&contents)?.migrate()),
+            FORMAT_VERSION => Ok(self.parse::<Document>(&contents)?.items),
+            version => Err(invalid_data(format!(
+                "{}: unsupported format version {}",
+                self.path.display(),
+                version
+            ))),
+        }
+    }
+    fn parse<'de, T: Deserialize<'de>>(&self, contents: &'de str) -> io::Result<T> {
+        serde_json::from_str(contents)
+            .map_err(|err| invalid_data(format!("{}: {}", self.path.display(), err)))
+    }
+    /// Writes the items to a temporary file and renames it over the old one,
+    /// so a crash mid-write never leaves a truncated file behind.
+    pub fn save(&self, items: &[Item]) -> io::Result<()> {
+        let document = DocumentRef {
+            version: FORMAT_VERSION,
+            items,
+        };
+        let contents = serde_json::to_string_pretty(&document).map_err(invalid_data)?;
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let tmp_path = self.path.with_extension("json.tmp");
+        fs::write(&tmp_path, contents)?;
+        fs::rename(&tmp_path, &self.path)
+    }
+}
+
+fn invalid_data<E>(err: E) -> io::Error
+where
+    E: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    io::Error::new(io::ErrorKind::InvalidData, err)
+}
@@ -0,0 +1,138 @@
+//! Follows log files like `tail -F`: new lines are picked up as they are
+//! written, and truncated or rotated files are read again from the start.
+use std::{
+    fs::{self, File, Metadata},
+    io::{self, Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+};
+
+/// Identifies the file behind a path, to notice when it was replaced.
+#[cfg(unix)]
+fn file_id(metadata: &Metadata) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+    Some((metadata.dev(), metadata.ino()))
+}
+
+#[cfg(not(unix))]
+fn file_id(_metadata: &Metadata) -> Option<(u64, u64)> {
+    None
+}
+
+pub struct Tail {
+    path: PathBuf,
+    file: Option<File>,
+    id: Option<(u64, u64)>,
+    offset: u64,
+    /// The start of a line whose newline was not written yet.
+    partial: Vec<u8>,
+    /// Set while polling fails, so each failure is only reported once.
+    failing: bool,
+}
+
+impl Tail {
+    /// Follows `path` from its current end. A file that does not exist yet is
+    /// read from the start once it appears.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        let mut tail = Self {
+            path: path.into(),
+            file: None,
+            id: None,
+            offset: 0,
+            partial: vec![],
+            failing: false,
+        };
+        if let Ok(metadata) = fs::metadata(&tail.path) {
+            if tail.open(&metadata).is_ok() {
+                tail.offset = metadata.len();
+            }
+        }
+        tail
+    }
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+    /// The file name, to tell the lines of several tailed files apart.
+    pub fn name(&self) -> String {
+        self.path
+            .file_name()
+            .unwrap_or(self.path.as_os_str())
+            .to_string_lossy()
+            .into_owned()
+    }
+    fn open(&mut self, metadata: &Metadata) -> io::Result<()> {
+        self.file = Some(File::open(&self.path)?);
+        self.id = file_id(metadata);
+        self.offset = 0;
+        self.partial.clear();
+        Ok(())
+    }
+    /// Reads what was appended since the last poll and returns the complete
+    /// lines. Errors are returned once, until polling succeeds again.
+    pub fn poll(&mut self) -> io::Result<Vec<String>> {
+        match self.read_lines() {
+            Ok(lines) => {
+                self.failing = false;
+                Ok(lines)
+            }
+            Err(_) if self.failing => Ok(vec![]),
+            Err(err) => {
+                self.failing = true;
+                Err(err)
+            }
+        }
+    }
+    fn read_lines(&mut self) -> io::Result<Vec<String>> {
+        let mut buffer = vec![];
+        // Whatever was still written to a rotated file comes first
+        self.read_appended(&mut buffer)?;
+        match fs::metadata(&self.path) {
+            Ok(metadata) => {
+                let replaced = self.file.is_none() || file_id(&metadata) != self.id;
+                if replaced {
+                    buffer = self.finish_partial(buffer);
+                    self.open(&metadata)?;
+                    self.read_appended(&mut buffer)?;
+                } else if metadata.len() < self.offset {
+                    // Truncated in place, e.g. by `> app.log` or copytruncate
+                    self.offset = 0;
+                    self.partial.clear();
+                    self.read_appended(&mut buffer)?;
+                }
+            }
+            // Rotated away and not recreated yet; keep the old file open
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+            Err(err) => return Err(err),
+        }
+        Ok(self.split_lines(buffer))
+    }
+    fn read_appended(&mut self, buffer: &mut Vec<u8>) -> io::Result<()> {
+        if let Some(file) = &mut self.file {
+            file.seek(SeekFrom::Start(self.offset))?;
+            let read = file.read_to_end(buffer)?;
+            self.offset += read as u64;
+        }
+        Ok(())
+    }
+    /// Ends the unterminated last line of a file that is being left behind.
+    fn finish_partial(&mut self, buffer: Vec<u8>) -> Vec<u8> {
+        let mut pending = std::mem::take(&mut self.partial);
+        pending.extend(buffer);
+        if pending.last().is_some_and(|byte| *byte != b'\n') {
+            pending.push(b'\n');
+        }
+        pending
+    }
+    fn split_lines(&mut self, buffer: Vec<u8>) -> Vec<String> {
+        self.partial.extend(buffer);
+        let end = match self.partial.iter().rposition(|byte| *byte == b'\n') {
+            Some(end) => end,
+            None => return vec![],
+        };
+        let complete = self.partial.drain(..=end).collect::<Vec<u8>>();
+        complete[..end]
+            .split(|byte| *byte == b'\n')
+            .map(|line| line.strip_suffix(b"\r").unwrap_or(line))
+            .map(|line| String::from_utf8_lossy(line).into_owned())
+            .collect()
+    }
+}
@@ -0,0 +1,76 @@
+//! Takes over the terminal for the UI, and makes sure it is given back
+//! however cotermi ends: normally, through an error, or through a panic.
+use crossterm::{
+    cursor::Show,
+    event::{DisableMouseCapture, EnableMouseCapture},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use std::{
+    io, panic,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Once,
+    },
+};
+
+/// Whether the terminal is currently taken over, so it is restored once.
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+static HOOK: Once = Once::new();
+
+/// Enters raw mode and the alternate screen with mouse capture.
+pub fn acquire() -> io::Result<()> {
+    enable_raw_mode()?;
+    ACTIVE.store(true, Ordering::SeqCst);
+    execute!(io::stdout(), EnterAlternateScreen, EnableMouseCapture)
+}
+
+/// Undoes [`acquire`] and shows the cursor; does nothing if the terminal is
+/// not taken over. Every step is tried even if one fails.
+pub fn release() -> io::Result<()> {
+    if !ACTIVE.swap(false, Ordering::SeqCst) {
+        return Ok(());
+    }
+    let raw = disable_raw_mode();
+    let screen = execute!(
+        io::stdout(),
+        LeaveAlternateScreen,
+        DisableMouseCapture,
+        Show
+    );
+    raw.and(screen)
+}
+
+/// Holds the terminal for as long as it lives. Dropping it, also while a
+/// panic unwinds, releases the terminal.
+pub struct Guard {
+    _private: (),
+}
+
+impl Guard {
+    /// Takes over the terminal. A panic on any thread releases it before
+    /// the panic message is printed, so that the message stays readable.
+    pub fn new() -> io::Result<Self> {
+        install_panic_hook();
+        let guard = Self { _private: () };
+        // Dropped on failure, which releases whatever was acquired
+        acquire()?;
+        Ok(guard)
+    }
+}
+
+impl Drop for Guard {
+    fn drop(&mut self) {
+        let _ = release();
+    }
+}
+
+fn install_panic_hook() {
+    HOOK.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            let _ = release();
+            previous(info);
+        }));
+    });
+}
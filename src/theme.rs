@@ -0,0 +1,288 @@
+use crate::events::Severity;
+use std::env;
+use tui::style::{Color, Modifier, Style};
+
+/// Styles for each event severity, from least to most severe.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SeverityStyles {
+    pub trace: Style,
+    pub debug: Style,
+    pub info: Style,
+    pub warning: Style,
+    pub error: Style,
+    pub critical: Style,
+}
+
+impl SeverityStyles {
+    pub fn get(&self, severity: Severity) -> Style {
+        match severity {
+            Severity::Trace => self.trace,
+            Severity::Debug => self.debug,
+            Severity::Info => self.info,
+            Severity::Warning => self.warning,
+            Severity::Error => self.error,
+            Severity::Critical => self.critical,
+        }
+    }
+}
+
+/// Every style the UI draws with.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Theme {
+    pub border: Style,
+    pub focused_border: Style,
+    /// The border of the events pane while an alert flashes it.
+    pub alert: Style,
+    pub highlight: Style,
+    pub item: Style,
+    /// Item body text, italic in all built-in themes.
+    pub body: Style,
+    pub title: Style,
+    pub meta: Style,
+    pub severity: SeverityStyles,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+impl Theme {
+    pub const NAMES: &'static [&'static str] = &["dark", "light", "high-contrast"];
+
+    pub fn dark() -> Self {
+        Self {
+            border: Style::default().fg(Color::Cyan),
+            focused_border: Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+            alert: Style::default()
+                .fg(Color::Red)
+                .add_modifier(Modifier::BOLD | Modifier::RAPID_BLINK),
+            highlight: Style::default().fg(Color::White).bg(Color::LightCyan),
+            item: Style::default().fg(Color::White),
+            body: Style::default().add_modifier(Modifier::ITALIC),
+            title: Style::default().add_modifier(Modifier::BOLD),
+            meta: Style::default().fg(Color::DarkGray),
+            severity: SeverityStyles {
+                trace: Style::default().fg(Color::DarkGray),
+                debug: Style::default().fg(Color::Blue),
+                info: Style::default().fg(Color::Green),
+                warning: Style::default().fg(Color::Yellow),
+                error: Style::default().fg(Color::Red),
+                critical: Style::default()
+                    .fg(Color::White)
+                    .bg(Color::Red)
+                    .add_modifier(Modifier::BOLD),
+            },
+        }
+    }
+    pub fn light() -> Self {
+        Self {
+            border: Style::default().fg(Color::DarkGray),
+            focused_border: Style::default()
+                .fg(Color::Blue)
+                .add_modifier(Modifier::BOLD),
+            alert: Style::default()
+                .fg(Color::Red)
+                .add_modifier(Modifier::BOLD | Modifier::RAPID_BLINK),
+            highlight: Style::default().fg(Color::Black).bg(Color::LightBlue),
+            item: Style::default().fg(Color::Black),
+            body: Style::default()
+                .fg(Color::DarkGray)
+                .add_modifier(Modifier::ITALIC),
+            title: Style::default()
+                .fg(Color::Black)
+                .add_modifier(Modifier::BOLD),
+            meta: Style::default().fg(Color::Gray),
+            severity: SeverityStyles {
+                trace: Style::default().fg(Color::Gray),
+                debug: Style::default().fg(Color::Blue),
+                info: Style::default().fg(Color::Green),
+                warning: Style::default().fg(Color::Magenta),
+                error: Style::default().fg(Color::Red),
+                critical: Style::default()
+                    .fg(Color::White)
+                    .bg(Color::Red)
+                    .add_modifier(Modifier::BOLD),
+            },
+        }
+    }
+    pub fn high_contrast() -> Self {
+        Self {
+            border: Style::default().fg(Color::White),
+            focused_border: Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+            alert: Style::default()
+                .fg(Color::LightRed)
+                .add_modifier(Modifier::BOLD | Modifier::RAPID_BLINK),
+            highlight: Style::default()
+                .fg(Color::Black)
+                .bg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+            item: Style::default().fg(Color::White),
+            body: Style::default()
+                .fg(Color::White)
+                .add_modifier(Modifier::ITALIC),
+            title: Style::default()
+                .fg(Color::White)
+                .add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+            meta: Style::default().fg(Color::Gray),
+            severity: SeverityStyles {
+                trace: Style::default().fg(Color::Gray),
+                debug: Style::default().fg(Color::LightBlue),
+                info: Style::default().fg(Color::LightGreen),
+                warning: Style::default()
+                    .fg(Color::LightYellow)
+                    .add_modifier(Modifier::BOLD),
+                error: Style::default()
+                    .fg(Color::LightRed)
+                    .add_modifier(Modifier::BOLD),
+                critical: Style::default()
+                    .fg(Color::Black)
+                    .bg(Color::LightRed)
+                    .add_modifier(Modifier::BOLD),
+            },
+        }
+    }
+    /// One of the built-in themes listed in [`Theme::NAMES`].
+    pub fn named(name: &str) -> Option<Self> {
+        match name {
+            "dark" => Some(Self::dark()),
+            "light" => Some(Self::light()),
+            "high-contrast" => Some(Self::high_contrast()),
+            _ => None,
+        }
+    }
+    /// Whether the user asked for no colors, see <https://no-color.org>.
+    pub fn no_color_requested() -> bool {
+        env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty())
+    }
+    /// The theme to draw with given whether [colors are
+    /// unwanted](Theme::no_color_requested).
+    pub fn apply_no_color(self, no_color: bool) -> Self {
+        match no_color {
+            true => self.without_colors(),
+            false => self,
+        }
+    }
+    /// Drops all colors, keeping only modifiers. Styles that were told apart
+    /// by color alone get a modifier so they stay distinguishable.
+    pub fn without_colors(self) -> Self {
+        let strip = |style: Style| Style::default().add_modifier(style.add_modifier);
+        Self {
+            border: strip(self.border),
+            focused_border: strip(self.focused_border).add_modifier(Modifier::BOLD),
+            alert: strip(self.alert).add_modifier(Modifier::BOLD | Modifier::REVERSED),
+            highlight: strip(self.highlight).add_modifier(Modifier::REVERSED),
+            item: strip(self.item),
+            body: strip(self.body),
+            title: strip(self.title),
+            meta: strip(self.meta).add_modifier(Modifier::DIM),
+            severity: SeverityStyles {
+                trace: strip(self.severity.trace).add_modifier(Modifier::DIM),
+                debug: strip(self.severity.debug).add_modifier(Modifier::DIM),
+                info: strip(self.severity.info),
+                warning: strip(self.severity.warning).add_modifier(Modifier::BOLD),
+                error: strip(self.severity.error).add_modifier(Modifier::BOLD),
+                critical: strip(self.severity.critical)
+                    .add_modifier(Modifier::BOLD | Modifier::REVERSED),
+            },
+        }
+    }
+    /// Overrides the style called `name` (as in the config file) with `spec`.
+    pub fn set(&mut self, name: &str, spec: &str) -> Result<(), String> {
+        let style = parse_style(spec)?;
+        let slot = match name {
+            "border" => &mut self.border,
+            "focused-border" => &mut self.focused_border,
+            "alert" => &mut self.alert,
+            "highlight" => &mut self.highlight,
+            "item" => &mut self.item,
+            "body" => &mut self.body,
+            "title" => &mut self.title,
+            "meta" => &mut self.meta,
+            "trace" => &mut self.severity.trace,
+            "debug" => &mut self.severity.debug,
+            "info" => &mut self.severity.info,
+            "warning" => &mut self.severity.warning,
+            "error" => &mut self.severity.error,
+            "critical" => &mut self.severity.critical,
+            name => return Err(format!("unknown style {:?}", name)),
+        };
+        *slot = style;
+        Ok(())
+    }
+}
+
+/// Parses styles like `red`, `white on blue bold` or `#ff8800 italic`.
+pub fn parse_style(spec: &str) -> Result<Style, String> {
+    let mut style = Style::default();
+    let mut words = spec.split_whitespace();
+    while let Some(word) = words.next() {
+        let word = word.to_ascii_lowercase();
+        if word == "on" {
+            let color = words
+                .next()
+                .ok_or_else(|| format!("missing background color after \"on\" in {:?}", spec))?;
+            style = style.bg(parse_color(color)?);
+        } else if let Some(modifier) = parse_modifier(&word) {
+            style = style.add_modifier(modifier);
+        } else {
+            style = style.fg(parse_color(&word)?);
+        }
+    }
+    Ok(style)
+}
+
+fn parse_modifier(name: &str) -> Option<Modifier> {
+    match name {
+        "bold" => Some(Modifier::BOLD),
+        "dim" => Some(Modifier::DIM),
+        "italic" => Some(Modifier::ITALIC),
+        "underlined" | "underline" => Some(Modifier::UNDERLINED),
+        "slow-blink" => Some(Modifier::SLOW_BLINK),
+        "rapid-blink" | "blink" => Some(Modifier::RAPID_BLINK),
+        "reversed" | "reverse" => Some(Modifier::REVERSED),
+        "hidden" => Some(Modifier::HIDDEN),
+        "crossed-out" => Some(Modifier::CROSSED_OUT),
+        _ => None,
+    }
+}
+
+fn parse_color(name: &str) -> Result<Color, String> {
+    let color = match name.to_ascii_lowercase().as_str() {
+        "reset" => Color::Reset,
+        "black" => Color::Black,
+        "red" => Color::Red,
+        "green" => Color::Green,
+        "yellow" => Color::Yellow,
+        "blue" => Color::Blue,
+        "magenta" => Color::Magenta,
+        "cyan" => Color::Cyan,
+        "gray" | "grey" => Color::Gray,
+        "darkgray" | "darkgrey" => Color::DarkGray,
+        "lightred" => Color::LightRed,
+        "lightgreen" => Color::LightGreen,
+        "lightyellow" => Color::LightYellow,
+        "lightblue" => Color::LightBlue,
+        "lightmagenta" => Color::LightMagenta,
+        "lightcyan" => Color::LightCyan,
+        "white" => Color::White,
+        hex if hex.starts_with('#') && hex.len() == 7 => {
+            // Sliced with `get`, as a multi-byte char could split any range
+            let channel = |range| u8::from_str_radix(hex.get(range)?, 16).ok();
+            match (channel(1..3), channel(3..5), channel(5..7)) {
+                (Some(r), Some(g), Some(b)) => Color::Rgb(r, g, b),
+                _ => return Err(format!("invalid color {:?}", name)),
+            }
+        }
+        index => match index.parse::<u8>() {
+            Ok(index) => Color::Indexed(index),
+            Err(_) => return Err(format!("unknown color or modifier {:?}", name)),
+        },
+    };
+    Ok(color)
+}
@@ -0,0 +1,119 @@
+//! A `tracing` layer that turns the events of the program embedding cotermi
+//! into events of its own, so the events pane doubles as a log viewer.
+use crate::{
+    event_loop::Producer,
+    events::{Event, Severity},
+};
+use std::fmt;
+use tracing::{
+    field::{Field, Visit},
+    span, Level, Subscriber,
+};
+use tracing_subscriber::{layer::Context, registry::LookupSpan};
+
+/// Sends every `tracing` event to the app that made the layer, see
+/// [`crate::App::tracing_layer`]. The event's fields and those of the spans
+/// it happened in become the fields of the logged event.
+pub struct Layer {
+    producer: Producer,
+}
+
+impl Layer {
+    pub fn new(producer: Producer) -> Self {
+        Self { producer }
+    }
+}
+
+pub fn severity(level: &Level) -> Severity {
+    match *level {
+        Level::TRACE => Severity::Trace,
+        Level::DEBUG => Severity::Debug,
+        Level::INFO => Severity::Info,
+        Level::WARN => Severity::Warning,
+        Level::ERROR => Severity::Error,
+    }
+}
+
+/// The fields recorded on a span so far, kept in its extensions.
+struct SpanFields(Vec<(String, String)>);
+
+#[derive(Default)]
+struct Visitor {
+    fields: Vec<(String, String)>,
+}
+
+impl Visitor {
+    /// Sets `name`, replacing the value an outer span gave it.
+    fn insert(&mut self, name: &str, value: String) {
+        match self.fields.iter_mut().find(|(key, _)| key == name) {
+            Some((_, old)) => *old = value,
+            None => self.fields.push((name.to_string(), value)),
+        }
+    }
+    fn take(&mut self, name: &str) -> Option<String> {
+        let index = self.fields.iter().position(|(key, _)| key == name)?;
+        Some(self.fields.remove(index).1)
+    }
+}
+
+impl Visit for Visitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.insert(field.name(), value.to_string());
+    }
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.insert(field.name(), format!("{:?}", value));
+    }
+}
+
+impl<S> tracing_subscriber::Layer<S> for Layer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &span::Attributes<'_>, id: &span::Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            let mut visitor = Visitor::default();
+            attrs.record(&mut visitor);
+            span.extensions_mut().insert(SpanFields(visitor.fields));
+        }
+    }
+
+    fn on_record(&self, id: &span::Id, values: &span::Record<'_>, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            if let Some(SpanFields(fields)) = span.extensions_mut().get_mut::<SpanFields>() {
+                let mut visitor = Visitor {
+                    fields: std::mem::take(fields),
+                };
+                values.record(&mut visitor);
+                *fields = visitor.fields;
+            }
+        }
+    }
+
+    fn on_event(&self, event: &tracing::Event<'_>, ctx: Context<'_, S>) {
+        let mut visitor = Visitor::default();
+        // Like `tracing`'s own formatter, the message is prefixed with the
+        // spans it happened in, outermost first
+        let mut spans = vec![];
+        if let Some(scope) = ctx.event_scope(event) {
+            for span in scope.from_root() {
+                spans.push(span.name());
+                if let Some(SpanFields(fields)) = span.extensions().get::<SpanFields>() {
+                    for (name, value) in fields {
+                        visitor.insert(name, value.clone());
+                    }
+                }
+            }
+        }
+        event.record(&mut visitor);
+        let mut message = visitor.take("message").unwrap_or_default();
+        if !spans.is_empty() {
+            message = format!("{}: {}", spans.join(":"), message);
+        }
+        let metadata = event.metadata();
+        let mut logged =
+            Event::new(severity(metadata.level()), message).with_source(metadata.target());
+        logged.fields = visitor.fields;
+        // Nothing to do once the app is gone
+        self.producer.event(logged);
+    }
+}
@@ -1,84 +1,166 @@
 pub mod list {
-    #[derive(Clone, Copy)]
+    #[derive(Clone, Copy, Debug)]
     pub enum Location {
         ExitPoint,
         Up,
         Down,
     }
-    #[derive(Clone, Copy)]
-    pub enum Operation<'a> {
+    #[derive(Clone, Debug)]
+    pub enum Operation {
         Go(Location),
-        New(&'a str, &'a str),
+        New(String, String),
+        Rename(String),
+        Describe(String),
+        Delete,
+        MoveUp,
+        MoveDown,
+    }
+}
+pub mod detail {
+    #[derive(Clone, Copy, Debug)]
+    pub enum Scroll {
+        Up,
+        Down,
+        PageUp,
+        PageDown,
+        Top,
+        Bottom,
+    }
+}
+pub mod events {
+    use crate::{events::Severity, export::Format};
+
+    /// Filtering and triage in the events pane.
+    #[derive(Clone, Copy, Debug)]
+    pub enum Operation {
+        Up,
+        Down,
+        Acknowledge,
+        AcknowledgeAll,
+        Snooze,
+        Resolve,
+        ToggleOutstanding,
+        Toggle(Severity),
+        RaiseThreshold,
+        LowerThreshold,
+        ToggleRegex,
+        Search,
+        ClearFilter,
+        /// Saves the events shown to a file.
+        Export(Format),
+        /// Shows or hides the event statistics header.
+        ToggleStats,
+    }
+}
+pub mod command {
+    /// Controls the commands whose output is streamed into the events.
+    #[derive(Clone, Copy, Debug)]
+    pub enum Operation {
+        Restart,
+        Kill,
+    }
+}
+pub mod input {
+    /// What a prompt collects input for.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum Kind {
+        New,
+        Rename,
+        Describe,
+    }
+    #[derive(Clone, Copy, Debug)]
+    pub enum Edit {
+        Insert(char),
+        Backspace,
+        Delete,
+        DeleteWord,
+        Left,
+        Right,
+        WordLeft,
+        WordRight,
+        Home,
+        End,
+        NextField,
+        PrevField,
+        Confirm,
+        Cancel,
     }
 }
 
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum Context {
     Default,
     List,
+    Detail,
+    Events,
+    Input,
 }
 pub mod context {
-    use super::{Action, Context};
-    use crossterm::event::{KeyCode, KeyEvent};
+    use super::Context;
 
     impl Context {
-        pub fn processs_input(&self, key: &KeyEvent) -> Option<Action> {
-            use super::list::*;
+        /// The name used for this context in the config file.
+        pub fn name(&self) -> &'static str {
             match self {
-                &Default => match key.code {
-                    KeyCode::Char('Q') | KeyCode::Char('q') => Some(Action::Quit),
-                    KeyCode::Char('l') => Some(Action::ChangeContext(Context::List)),
-                    KeyCode::Left => Some(Action::ListSignal(Operation::Go(Location::ExitPoint))),
-                    KeyCode::Up => Some(Action::ListSignal(Operation::Go(Location::Up))),
-                    KeyCode::Down => Some(Action::ListSignal(Operation::Go(Location::Down))),
-                    _ => None,
-                },
-                &List => match key.code {
-                    KeyCode::Char('Q') => Some(Action::Quit),
-                    KeyCode::Char('q') | KeyCode::Right | KeyCode::Esc => {
-                        Some(Action::ChangeContext(Context::Default))
-                    }
-                    KeyCode::Up => Some(Action::ListSignal(Operation::Go(Location::Up))),
-                    KeyCode::Down => Some(Action::ListSignal(Operation::Go(Location::Down))),
-                    KeyCode::Char('N') | KeyCode::Char('n') => {
-                        let new_title = "title";
-                        let new_desc = "desc";
-                        Some(Action::ListSignal(Operation::New(new_title, new_desc)))
-                    }
-                    _ => None,
-                    _ => todo!(),
-                },
+                Context::Default => "default",
+                Context::List => "list",
+                Context::Detail => "detail",
+                Context::Events => "events",
+                Context::Input => "input",
             }
         }
+        pub fn from_name(name: &str) -> Option<Context> {
+            [
+                Context::Default,
+                Context::List,
+                Context::Detail,
+                Context::Events,
+                Context::Input,
+            ]
+            .into_iter()
+            .find(|context| context.name() == name)
+        }
     }
 }
-pub struct Signal<'a> {
-    stype: SType,
-    action: Action<'a>,
+/// An action waiting in the app's queue until the next tick.
+#[derive(Clone, Debug)]
+pub struct Signal {
+    pub stype: SType,
+    pub action: Action,
+}
+impl Signal {
+    /// A signal for `action`, top-level if it concerns the whole app.
+    pub fn new(action: Action) -> Self {
+        let stype = match action {
+            Action::Quit | Action::Resize(..) | Action::Suspend => SType::TopLevel,
+            _ => SType::Contextual,
+        };
+        Self { stype, action }
+    }
 }
+/// Top-level signals preempt contextual ones waiting in the same queue.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum SType {
     TopLevel,
     Contextual,
 }
-pub enum Action<'a> {
+#[derive(Clone, Debug)]
+pub enum Action {
     Quit,
+    /// The terminal was resized to the given columns and rows.
+    Resize(u16, u16),
+    /// Hands the terminal back to the shell until resumed.
+    Suspend,
+    /// A key press, looked up in the keymap of whatever context is current
+    /// when it is processed.
+    Key(crossterm::event::KeyEvent),
     ChangeContext(Context),
-    ListSignal(list::Operation<'a>),
-}
-pub mod signal {
-    use super::Action;
-    trait Contextual {}
-    // impl Contextual for super::Action::
-    // trait TopLevel {
-    // fn process(&self) -> Fn();
-    // }
-    impl<'a> Action<'a> {
-        fn perform(&self) {
-            match self {
-                Action::Quit => {}
-                Action::ChangeContext(target_context) => {}
-                Action::ListSignal(operation) => {}
-            }
-        }
-    }
+    ListSignal(list::Operation),
+    DetailSignal(detail::Scroll),
+    EventSignal(events::Operation),
+    CommandSignal(command::Operation),
+    Undo,
+    Redo,
+    Prompt(input::Kind),
+    InputSignal(input::Edit),
 }
@@ -0,0 +1,844 @@
+use cotermi::{
+    events::{Event, EventLog, Severity},
+    harness::{chord, key, Harness},
+    item::Item,
+    utils::{Action, Context, Signal},
+    App,
+};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+fn harness() -> Harness {
+    let items = vec![
+        Item::new(0, "Alpha", "first"),
+        Item::new(1, "Beta", "second"),
+        Item::new(2, "Gamma", "third"),
+    ];
+    let mut harness = Harness::new(App::with_items(items), 80, 20).unwrap();
+    harness.render().unwrap();
+    harness
+}
+
+fn titles(harness: &Harness) -> Vec<&str> {
+    let items = harness.app.items().iter();
+    items.map(|item| item.title.as_str()).collect()
+}
+
+fn undo() -> KeyEvent {
+    key(KeyCode::Char('u'))
+}
+
+fn redo() -> KeyEvent {
+    chord(KeyCode::Char('r'), KeyModifiers::CONTROL)
+}
+
+#[test]
+fn starts_in_default_context_without_selection() {
+    let harness = harness();
+    assert_eq!(harness.app.context(), Context::Default);
+    assert_eq!(harness.app.selected(), None);
+    assert!(harness.contains("Alpha"));
+    assert!(harness.contains("No item selected"));
+}
+
+#[test]
+fn list_context_is_entered_and_left() {
+    let mut harness = harness();
+    harness.press(key(KeyCode::Char('l'))).unwrap();
+    assert_eq!(harness.app.context(), Context::List);
+    assert_eq!(harness.app.selected(), Some(0));
+
+    harness.press(key(KeyCode::Down)).unwrap();
+    harness.press(key(KeyCode::Esc)).unwrap();
+    assert_eq!(harness.app.context(), Context::Default);
+    assert_eq!(harness.app.selected(), None);
+    assert_eq!(harness.app.exit_point(), Some(1));
+
+    // Re-entering picks up where the list was left
+    harness.press(key(KeyCode::Char('l'))).unwrap();
+    assert_eq!(harness.app.selected(), Some(1));
+}
+
+#[test]
+fn moving_to_the_focused_context_does_nothing() {
+    let config = cotermi::config::Config::parse("[keys.list]\nx = \"context.list\"\n").unwrap();
+    let items = vec![Item::new(0, "Alpha", ""), Item::new(1, "Beta", "")];
+    let app = App::with_items(items).with_config(config);
+    let mut harness = Harness::new(app, 80, 20).unwrap();
+    harness
+        .keys([
+            key(KeyCode::Char('l')),
+            key(KeyCode::Down),
+            key(KeyCode::Char('x')),
+        ])
+        .unwrap();
+    assert_eq!(harness.app.context(), Context::List);
+    assert_eq!(harness.app.selected(), Some(1));
+}
+
+#[test]
+fn tab_cycles_through_contexts() {
+    let mut harness = harness();
+    let tab = || key(KeyCode::Tab);
+    harness.press(tab()).unwrap();
+    assert_eq!(harness.app.context(), Context::List);
+    harness.press(tab()).unwrap();
+    assert_eq!(harness.app.context(), Context::Detail);
+    assert!(harness.contains("Details: Alpha"));
+    harness.press(tab()).unwrap();
+    assert_eq!(harness.app.context(), Context::Default);
+
+    harness
+        .press(chord(KeyCode::BackTab, KeyModifiers::SHIFT))
+        .unwrap();
+    assert_eq!(harness.app.context(), Context::Detail);
+    harness.press(key(KeyCode::Left)).unwrap();
+    assert_eq!(harness.app.context(), Context::List);
+}
+
+#[test]
+fn detail_pane_scrolls_to_the_last_wrapped_line_and_no_further() {
+    let mut body = (1..=30)
+        .map(|line| format!("body {:02}", line))
+        .collect::<Vec<String>>();
+    // Wraps over several rows of the pane
+    body[14] = "long ".repeat(30);
+    let items = vec![
+        Item::new(0, "Alpha", "first").with_body(body),
+        Item::new(1, "Beta", "second"),
+    ];
+    let mut harness = Harness::new(App::with_items(items), 80, 20).unwrap();
+    harness
+        .keys([key(KeyCode::Char('l')), key(KeyCode::Enter)])
+        .unwrap();
+    assert_eq!(harness.app.context(), Context::Detail);
+    assert!(harness.contains("id: 0"));
+    assert!(!harness.contains("body 30"));
+
+    // The last line sits right above the bottom border
+    let at_bottom = |harness: &Harness| {
+        let lines = harness.lines();
+        let row = lines.iter().position(|line| line.contains("body 30"));
+        row.is_some_and(|row| lines[row + 1].contains('┗'))
+    };
+    harness.press(key(KeyCode::End)).unwrap();
+    assert!(at_bottom(&harness));
+    let bottom = harness.lines();
+    for code in [KeyCode::PageDown, KeyCode::Down, KeyCode::Char(' ')] {
+        harness.press(key(code)).unwrap();
+        assert_eq!(harness.lines(), bottom);
+    }
+
+    harness.press(key(KeyCode::Up)).unwrap();
+    assert!(!harness.contains("body 30"));
+    harness.press(key(KeyCode::Down)).unwrap();
+    assert!(at_bottom(&harness));
+    harness.press(key(KeyCode::PageUp)).unwrap();
+    assert!(!harness.contains("body 30"));
+    assert!(!harness.contains("id: 0"));
+    harness.press(key(KeyCode::Home)).unwrap();
+    assert!(harness.contains("id: 0"));
+
+    // Another item starts at the top
+    harness
+        .keys([
+            key(KeyCode::End),
+            key(KeyCode::Left),
+            key(KeyCode::Down),
+            key(KeyCode::Enter),
+        ])
+        .unwrap();
+    assert!(harness.contains("Details: Beta"));
+    assert!(harness.contains("id: 1"));
+    harness
+        .keys([key(KeyCode::Left), key(KeyCode::Up), key(KeyCode::Enter)])
+        .unwrap();
+    assert!(harness.contains("id: 0"));
+}
+
+#[test]
+fn list_navigation_wraps_around() {
+    let mut harness = harness();
+    harness.press(key(KeyCode::Char('l'))).unwrap();
+    harness.press(key(KeyCode::Up)).unwrap();
+    assert_eq!(harness.app.selected(), Some(2));
+    harness.press(key(KeyCode::Down)).unwrap();
+    assert_eq!(harness.app.selected(), Some(0));
+    harness
+        .keys([key(KeyCode::Down), key(KeyCode::Down)])
+        .unwrap();
+    assert_eq!(harness.app.selected(), Some(2));
+    assert!(harness.contains(">>Gamma"));
+}
+
+#[test]
+fn default_context_navigation_moves_from_exit_point() {
+    let mut harness = harness();
+    harness
+        .keys([
+            key(KeyCode::Char('l')),
+            key(KeyCode::Down),
+            key(KeyCode::Esc),
+        ])
+        .unwrap();
+    harness.press(key(KeyCode::Down)).unwrap();
+    assert_eq!(harness.app.selected(), Some(2));
+}
+
+#[test]
+fn default_context_left_selects_exit_point() {
+    let mut harness = harness();
+    harness
+        .keys([key(KeyCode::Char('l')), key(KeyCode::Up), key(KeyCode::Esc)])
+        .unwrap();
+    harness.press(key(KeyCode::Left)).unwrap();
+    assert_eq!(harness.app.selected(), Some(2));
+}
+
+#[test]
+fn item_is_created_through_the_prompt() {
+    let mut harness = harness();
+    harness.press(key(KeyCode::Char('l'))).unwrap();
+    harness.press(key(KeyCode::Char('n'))).unwrap();
+    assert_eq!(harness.app.context(), Context::Input);
+    assert!(harness.contains("New item"));
+
+    harness.type_str("Groceries").unwrap();
+    harness.press(key(KeyCode::Enter)).unwrap();
+    harness.type_str("milk eggs").unwrap();
+    harness
+        .press(chord(KeyCode::Char('w'), KeyModifiers::CONTROL))
+        .unwrap();
+    harness.type_str("bread").unwrap();
+    harness.press(key(KeyCode::Enter)).unwrap();
+
+    assert_eq!(harness.app.context(), Context::List);
+    let created = harness.app.items().last().unwrap();
+    assert_eq!(created.title, "Groceries");
+    assert_eq!(created.description, "milk bread");
+    assert_eq!(created.id, 3);
+    assert_eq!(harness.app.selected(), Some(3));
+    assert!(harness.contains(">>Groceries"));
+    assert!(harness.contains("milk bread"));
+}
+
+#[test]
+fn item_can_be_created_from_default_context() {
+    let mut harness = harness();
+    harness.press(key(KeyCode::Char('n'))).unwrap();
+    harness.type_str("Later").unwrap();
+    harness
+        .keys([key(KeyCode::Enter), key(KeyCode::Enter)])
+        .unwrap();
+    assert_eq!(harness.app.context(), Context::Default);
+    assert_eq!(harness.app.items().len(), 4);
+    assert_eq!(harness.app.exit_point(), Some(3));
+}
+
+#[test]
+fn prompt_requires_a_title_and_can_be_cancelled() {
+    let mut harness = harness();
+    harness.press(key(KeyCode::Char('n'))).unwrap();
+    harness.press(key(KeyCode::Enter)).unwrap();
+    assert_eq!(harness.app.context(), Context::Input);
+
+    harness.type_str("Discarded").unwrap();
+    harness.press(key(KeyCode::Esc)).unwrap();
+    assert_eq!(harness.app.context(), Context::Default);
+    assert_eq!(harness.app.items().len(), 3);
+    assert!(!harness.contains("Discarded"));
+}
+
+#[test]
+fn prompt_fields_are_cycled_both_ways() {
+    let mut harness = harness();
+    let backtab = || chord(KeyCode::BackTab, KeyModifiers::SHIFT);
+    harness.press(key(KeyCode::Char('n'))).unwrap();
+    harness.type_str("Later").unwrap();
+    // From the first field back to the last one, and back again
+    harness.press(backtab()).unwrap();
+    harness.type_str("eventually").unwrap();
+    harness.press(backtab()).unwrap();
+    harness.type_str("!").unwrap();
+    harness.press(key(KeyCode::Tab)).unwrap();
+    harness.type_str(", maybe").unwrap();
+    harness.press(key(KeyCode::Tab)).unwrap();
+    harness.type_str("?").unwrap();
+    harness
+        .keys([key(KeyCode::Enter), key(KeyCode::Enter)])
+        .unwrap();
+    assert_eq!(harness.app.context(), Context::Default);
+    let item = &harness.app.items()[3];
+    assert_eq!(item.title, "Later!?");
+    assert_eq!(item.description, "eventually, maybe");
+}
+
+#[test]
+fn selected_item_is_renamed_and_described() {
+    let mut harness = harness();
+    harness
+        .keys([
+            key(KeyCode::Char('l')),
+            key(KeyCode::Down),
+            key(KeyCode::Char('r')),
+        ])
+        .unwrap();
+    assert!(harness.contains("Rename item"));
+    harness
+        .press(chord(KeyCode::Char('w'), KeyModifiers::CONTROL))
+        .unwrap();
+    harness.type_str("Bravo").unwrap();
+    harness.press(key(KeyCode::Enter)).unwrap();
+    assert_eq!(harness.app.context(), Context::List);
+    assert_eq!(titles(&harness), ["Alpha", "Bravo", "Gamma"]);
+    assert_eq!(harness.app.selected(), Some(1));
+
+    harness.press(key(KeyCode::Char('e'))).unwrap();
+    assert!(harness.contains("Edit description"));
+    harness.type_str(" half").unwrap();
+    harness.press(key(KeyCode::Enter)).unwrap();
+    let item = &harness.app.items()[1];
+    assert_eq!((item.title.as_str(), item.id), ("Bravo", 1));
+    assert_eq!(item.description, "second half");
+    assert!(harness.contains("second half"));
+}
+
+#[test]
+fn deleting_selects_the_next_item_or_the_new_last_one() {
+    let mut harness = harness();
+    harness
+        .keys([
+            key(KeyCode::Char('l')),
+            key(KeyCode::Down),
+            key(KeyCode::Char('d')),
+        ])
+        .unwrap();
+    assert_eq!(titles(&harness), ["Alpha", "Gamma"]);
+    assert_eq!(harness.app.selected(), Some(1));
+    assert!(harness.contains(">>Gamma"));
+
+    harness.press(key(KeyCode::Char('d'))).unwrap();
+    assert_eq!(titles(&harness), ["Alpha"]);
+    assert_eq!(harness.app.selected(), Some(0));
+    harness.press(key(KeyCode::Char('d'))).unwrap();
+    assert!(harness.app.items().is_empty());
+    assert_eq!(harness.app.selected(), None);
+    // Nothing left to delete
+    harness.press(key(KeyCode::Char('d'))).unwrap();
+    assert_eq!(harness.app.selected(), None);
+}
+
+#[test]
+fn selected_item_moves_up_and_down_within_the_list() {
+    let mut harness = harness();
+    harness
+        .keys([key(KeyCode::Char('l')), key(KeyCode::Char('J'))])
+        .unwrap();
+    assert_eq!(titles(&harness), ["Beta", "Alpha", "Gamma"]);
+    assert_eq!(harness.app.selected(), Some(1));
+    harness
+        .press(chord(KeyCode::Down, KeyModifiers::SHIFT))
+        .unwrap();
+    assert_eq!(titles(&harness), ["Beta", "Gamma", "Alpha"]);
+    assert_eq!(harness.app.selected(), Some(2));
+    // Moves past either end do not wrap around
+    harness.press(key(KeyCode::Char('J'))).unwrap();
+    assert_eq!(titles(&harness), ["Beta", "Gamma", "Alpha"]);
+    assert_eq!(harness.app.selected(), Some(2));
+
+    harness
+        .keys([
+            key(KeyCode::Char('K')),
+            chord(KeyCode::Up, KeyModifiers::SHIFT),
+            key(KeyCode::Char('K')),
+        ])
+        .unwrap();
+    assert_eq!(titles(&harness), ["Alpha", "Beta", "Gamma"]);
+    assert_eq!(harness.app.selected(), Some(0));
+}
+
+#[test]
+fn deleting_the_last_item_is_undone_and_redone() {
+    let mut harness = harness();
+    harness
+        .keys([
+            key(KeyCode::Char('l')),
+            key(KeyCode::Up),
+            key(KeyCode::Char('d')),
+        ])
+        .unwrap();
+    assert_eq!(titles(&harness), ["Alpha", "Beta"]);
+    assert_eq!(harness.app.selected(), Some(1));
+
+    harness.press(undo()).unwrap();
+    assert_eq!(titles(&harness), ["Alpha", "Beta", "Gamma"]);
+    assert_eq!(harness.app.selected(), Some(2));
+    assert!(harness.contains(">>Gamma"));
+    harness.press(redo()).unwrap();
+    assert_eq!(titles(&harness), ["Alpha", "Beta"]);
+    assert_eq!(harness.app.selected(), Some(1));
+}
+
+#[test]
+fn moves_are_undone_with_the_selection() {
+    let mut harness = harness();
+    harness
+        .keys([key(KeyCode::Char('l')), key(KeyCode::Char('J'))])
+        .unwrap();
+    assert_eq!(titles(&harness), ["Beta", "Alpha", "Gamma"]);
+    assert_eq!(harness.app.selected(), Some(1));
+
+    harness.press(undo()).unwrap();
+    assert_eq!(titles(&harness), ["Alpha", "Beta", "Gamma"]);
+    assert_eq!(harness.app.selected(), Some(0));
+}
+
+#[test]
+fn items_created_from_default_context_are_undone_there() {
+    let mut harness = harness();
+    harness.press(key(KeyCode::Char('n'))).unwrap();
+    harness.type_str("Later").unwrap();
+    harness
+        .keys([key(KeyCode::Enter), key(KeyCode::Enter)])
+        .unwrap();
+    assert_eq!(harness.app.exit_point(), Some(3));
+
+    harness.press(undo()).unwrap();
+    assert_eq!(harness.app.context(), Context::Default);
+    assert_eq!(titles(&harness), ["Alpha", "Beta", "Gamma"]);
+    assert_eq!(harness.app.selected(), None);
+    assert_eq!(harness.app.exit_point(), None);
+    harness.press(redo()).unwrap();
+    assert_eq!(titles(&harness), ["Alpha", "Beta", "Gamma", "Later"]);
+    assert_eq!(harness.app.selected(), None);
+    assert_eq!(harness.app.exit_point(), Some(3));
+}
+
+#[test]
+fn new_changes_clear_what_can_be_redone() {
+    let mut harness = harness();
+    harness
+        .keys([
+            key(KeyCode::Char('l')),
+            key(KeyCode::Char('J')),
+            undo(),
+            key(KeyCode::Down),
+            key(KeyCode::Char('J')),
+            redo(),
+        ])
+        .unwrap();
+    assert_eq!(titles(&harness), ["Alpha", "Gamma", "Beta"]);
+    assert_eq!(harness.app.selected(), Some(2));
+}
+
+#[test]
+fn only_the_latest_changes_are_undone() {
+    let mut harness = harness();
+    harness.press(key(KeyCode::Char('l'))).unwrap();
+    // One move more than the history keeps leaves Alpha second
+    let depth = cotermi::history::DEFAULT_DEPTH;
+    for moved in 0..=depth {
+        let code = match moved % 2 {
+            0 => KeyCode::Char('J'),
+            _ => KeyCode::Char('K'),
+        };
+        harness.press(key(code)).unwrap();
+    }
+    assert_eq!(titles(&harness), ["Beta", "Alpha", "Gamma"]);
+
+    harness.keys((0..=depth).map(|_| undo())).unwrap();
+    assert_eq!(titles(&harness), ["Beta", "Alpha", "Gamma"]);
+}
+
+#[test]
+fn quit_stops_the_app() {
+    let mut harness = harness();
+    assert!(harness.app.is_running());
+    harness.press(key(KeyCode::Char('q'))).unwrap();
+    assert!(!harness.app.is_running());
+}
+
+#[test]
+fn keys_wait_for_the_tick_and_follow_the_context_they_land_in() {
+    let mut harness = harness();
+    // `q` quits from the default context but only leaves the list
+    harness.app.handle_key(&key(KeyCode::Char('l')));
+    harness.app.handle_key(&key(KeyCode::Char('q')));
+    assert_eq!(harness.app.context(), Context::Default);
+    harness.tick().unwrap();
+    assert_eq!(harness.app.context(), Context::Default);
+    assert!(harness.app.is_running());
+    assert!(harness.contains("List"));
+}
+
+#[test]
+fn top_level_signals_preempt_contextual_ones() {
+    let mut harness = harness();
+    harness
+        .app
+        .enqueue(Signal::new(Action::ChangeContext(Context::List)));
+    harness.app.enqueue(Signal::new(Action::Quit));
+    harness.app.process_signals().unwrap();
+    assert!(!harness.app.is_running());
+    assert_eq!(harness.app.context(), Context::Default);
+
+    // Non-keyboard sources queue up too
+    let mut harness = self::harness();
+    harness.app.enqueue(Signal::new(Action::Resize(100, 30)));
+    harness
+        .app
+        .enqueue(Signal::new(Action::ChangeContext(Context::Events)));
+    harness.tick().unwrap();
+    assert_eq!(harness.app.context(), Context::Events);
+}
+
+#[test]
+fn events_pane_shows_newest_events_and_drops_the_oldest() {
+    let app = App::with_items(vec![]).with_event_log(EventLog::with_capacity(2));
+    let mut harness = Harness::new(app, 80, 20).unwrap();
+    harness.app.push_event(Event::new(Severity::Info, "first"));
+    harness
+        .app
+        .push_event(Event::new(Severity::Warning, "second"));
+    harness.app.push_event(Event::new(Severity::Error, "third"));
+    harness.render().unwrap();
+
+    assert_eq!(harness.app.events().len(), 2);
+    assert!(harness.contains("Events (2, 1 dropped)"));
+    assert!(!harness.contains("first"));
+    assert!(harness.contains("WARNING  second"));
+    assert!(harness.contains("ERROR    third"));
+}
+
+#[test]
+fn events_are_filtered_by_severity_and_text() {
+    let mut harness = harness();
+    harness
+        .app
+        .push_event(Event::new(Severity::Debug, "cache warmed"));
+    harness
+        .app
+        .push_event(Event::new(Severity::Info, "disk mounted"));
+    harness
+        .app
+        .push_event(Event::new(Severity::Error, "disk full"));
+    harness.press(key(KeyCode::Char('e'))).unwrap();
+    assert_eq!(harness.app.context(), Context::Events);
+
+    harness
+        .keys([key(KeyCode::Char('+')), key(KeyCode::Char('+'))])
+        .unwrap();
+    assert!(!harness.contains("cache warmed"));
+    assert!(harness.contains("Events (2/3) [>=INFO]"));
+
+    harness.press(key(KeyCode::Char('5'))).unwrap();
+    assert!(!harness.contains("disk full"));
+    harness.press(key(KeyCode::Char('5'))).unwrap();
+
+    harness.press(key(KeyCode::Char('/'))).unwrap();
+    harness.type_str("FULL").unwrap();
+    assert!(!harness.contains("disk mounted"));
+    assert!(harness.contains("disk full"));
+    harness.press(key(KeyCode::Enter)).unwrap();
+    assert_eq!(harness.app.context(), Context::Events);
+    assert!(harness.contains(r#"[>=INFO "FULL"]"#));
+
+    harness.press(key(KeyCode::Char('c'))).unwrap();
+    assert!(harness.contains("Events (3)"));
+}
+
+#[test]
+fn events_search_supports_regexes_and_cancelling() {
+    let mut harness = harness();
+    harness
+        .app
+        .push_event(Event::new(Severity::Info, "request 200"));
+    harness
+        .app
+        .push_event(Event::new(Severity::Warning, "request 503"));
+    harness
+        .keys([key(KeyCode::Char('e')), key(KeyCode::Char('r'))])
+        .unwrap();
+    harness.press(key(KeyCode::Char('/'))).unwrap();
+    harness.type_str("5\\d+").unwrap();
+    assert!(!harness.contains("request 200"));
+    assert!(harness.contains("Regex: 5\\d+"));
+
+    harness.press(key(KeyCode::Esc)).unwrap();
+    assert_eq!(harness.app.filter().query(), "");
+    assert!(harness.contains("request 200"));
+
+    harness.press(key(KeyCode::Char('/'))).unwrap();
+    harness.type_str("(").unwrap();
+    assert!(harness.contains("/(/ (invalid)"));
+    assert!(harness.contains("request 200"));
+}
+
+#[test]
+fn tailed_lines_appear_as_events() {
+    let path = std::env::temp_dir().join(format!("cotermi-app-{}.log", std::process::id()));
+    std::fs::write(&path, "").unwrap();
+    let app = App::with_items(vec![]).with_tail(&path);
+    let mut harness = Harness::new(app, 80, 20).unwrap();
+    std::fs::write(&path, "service started\n").unwrap();
+    harness.tick().unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert!(harness.contains("INFO     [cotermi-app-"));
+    assert!(harness.contains("service started"));
+}
+
+#[cfg(unix)]
+#[test]
+fn command_output_and_exit_status_become_events() {
+    let app = App::with_items(vec![]).with_command("echo building; echo oops >&2; exit 1");
+    let mut harness = Harness::new(app, 100, 20).unwrap();
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+    while !harness.contains("ended with") && std::time::Instant::now() < deadline {
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        harness.tick().unwrap();
+    }
+    assert!(harness.contains("INFO     [echo] building"));
+    assert!(harness.contains("WARNING  [echo] oops"));
+    assert!(harness.contains("ERROR    [echo] echo building; echo oops >&2; exit 1 ended"));
+}
+
+#[test]
+fn alert_rules_escalate_label_and_count_events() {
+    let config = cotermi::config::Config::parse(
+        r#"
+        [[rules]]
+        pattern = "panicked at"
+        severity = "critical"
+        label = "panic"
+        actions = ["flash", "bell"]
+        "#,
+    )
+    .unwrap();
+    let app = App::with_items(vec![]).with_config(config);
+    let mut harness = Harness::new(app, 100, 20).unwrap();
+    harness
+        .app
+        .push_event(Event::new(Severity::Info, "all good"));
+    harness.app.push_event(Event::new(
+        Severity::Info,
+        "thread 'main' panicked at src/main.rs:3:5",
+    ));
+    harness.render().unwrap();
+
+    assert!(harness.contains("CRITICAL panic: thread 'main' panicked at"));
+    assert!(harness.contains("1 alert: panic 1"));
+    assert!(harness.app.take_bell());
+    assert!(!harness.app.take_bell());
+    let flashing = harness
+        .buffer()
+        .content
+        .iter()
+        .any(|cell| cell.modifier.contains(tui::style::Modifier::RAPID_BLINK));
+    assert!(flashing);
+}
+
+#[test]
+fn alert_rules_match_structured_fields() {
+    let config = cotermi::config::Config::parse(
+        r#"
+        [[rules]]
+        fields = { status = "^5\\d\\d$", route = "/checkout" }
+        label = "checkout down"
+        "#,
+    )
+    .unwrap();
+    let app = App::with_items(vec![]).with_config(config);
+    let mut harness = Harness::new(app, 100, 20).unwrap();
+    let parser = cotermi::parser::Parser::default();
+    for line in [
+        r#"{"msg":"served","status":503,"route":"/checkout/pay"}"#,
+        r#"{"msg":"served","status":200,"route":"/checkout/pay"}"#,
+        "msg=served status=502 route=/cart",
+        "msg=served status=500 route=/checkout",
+    ] {
+        harness.app.push_event(parser.parse(line));
+    }
+    harness.render().unwrap();
+
+    let labels = harness
+        .app
+        .events()
+        .iter()
+        .map(|event| event.label.as_deref())
+        .collect::<Vec<_>>();
+    let matched = Some("checkout down");
+    assert_eq!(labels, [matched, None, None, matched]);
+    assert!(harness.contains("2 alerts: checkout down 2"));
+
+    let err = cotermi::config::Config::parse("[[rules]]\nfields = { status = \"(\" }\n")
+        .err()
+        .unwrap();
+    assert!(err.contains("#1: fields.status: invalid regex"), "{}", err);
+}
+
+#[test]
+fn invalid_alert_rules_are_reported() {
+    let err = cotermi::config::Config::parse(
+        r#"
+        [[rules]]
+        label = "matches nothing"
+
+        [[rules]]
+        level = "error"
+        actions = ["page"]
+        "#,
+    )
+    .err()
+    .unwrap();
+    assert!(
+        err.contains("#1: needs a pattern, source, level or fields"),
+        "{}",
+        err
+    );
+    assert!(
+        err.contains(r#"#2: actions: unknown action "page""#),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn events_are_triaged_from_the_events_context() {
+    let mut harness = harness();
+    harness
+        .app
+        .push_event(Event::new(Severity::Info, "deployed"));
+    harness
+        .app
+        .push_event(Event::new(Severity::Error, "db timeout"));
+    harness
+        .app
+        .push_event(Event::new(Severity::Critical, "db down"));
+    harness
+        .app
+        .push_event(Event::new(Severity::Warning, "slow query"));
+    harness.press(key(KeyCode::Char('e'))).unwrap();
+    assert!(harness.contains("outstanding: CRITICAL 1 ERROR 1 WARNING 1"));
+
+    // Newest first: acknowledge the warning, snooze the critical, resolve the error
+    harness.press(key(KeyCode::Up)).unwrap();
+    assert_eq!(harness.app.selected_event().unwrap().message, "slow query");
+    harness.press(key(KeyCode::Char('a'))).unwrap();
+    harness
+        .keys([key(KeyCode::Up), key(KeyCode::Char('s'))])
+        .unwrap();
+    harness
+        .keys([key(KeyCode::Up), key(KeyCode::Char('x'))])
+        .unwrap();
+    assert!(harness.contains("(acknowledged) slow query"));
+    assert!(harness.contains("(snoozed) db down"));
+    assert!(harness.contains("(resolved) db timeout"));
+    assert!(!harness.contains("outstanding:"));
+
+    harness
+        .app
+        .push_event(Event::new(Severity::Error, "disk full"));
+    harness.press(key(KeyCode::Char('o'))).unwrap();
+    assert!(harness.contains("Events (1/5) [outstanding]"));
+    assert!(!harness.contains("deployed"));
+    harness.press(key(KeyCode::Char('A'))).unwrap();
+    assert!(harness.contains("Events (0/5) [outstanding]"));
+}
+
+#[test]
+fn shown_events_are_exported_from_the_events_context() {
+    let dir = std::env::temp_dir().join(format!("cotermi-export-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let app = App::with_items(vec![]).with_export_dir(&dir);
+    let mut harness = Harness::new(app, 80, 20).unwrap();
+    harness
+        .app
+        .push_event(Event::new(Severity::Debug, "cache warmed"));
+    harness
+        .app
+        .push_event(Event::new(Severity::Error, "db timeout"));
+    harness
+        .keys([
+            key(KeyCode::Char('e')),
+            key(KeyCode::Char('2')),
+            key(KeyCode::Char('W')),
+        ])
+        .unwrap();
+
+    let exported = harness.app.events().iter().last().unwrap().message.clone();
+    assert!(
+        exported.starts_with("exported 1 events to "),
+        "{}",
+        exported
+    );
+    let path = std::fs::read_dir(&dir)
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap()
+        .path();
+    assert_eq!(path.extension().unwrap(), "csv");
+    let contents = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(contents.lines().count(), 2);
+    assert!(contents.contains(",ERROR,,,open,db timeout"));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn stats_header_charts_event_rate_and_severities() {
+    let mut log = EventLog::with_capacity(10);
+    log.push(Event::new(Severity::Error, "one"));
+    log.push(Event::new(Severity::Error, "two"));
+    log.push(Event::new(Severity::Info, "three"));
+    let now = cotermi::events::now_millis();
+    let rate = log.rate(now, 60, 1);
+    assert_eq!(rate.len(), 60);
+    // Seconds may have ticked over between the pushes and `now`
+    assert_eq!(rate[57..].iter().sum::<u64>(), 3);
+    assert_eq!(log.rate(now + 120_000, 60, 1), vec![0; 60]);
+    let rate = log.rate(now, 10, 30);
+    assert_eq!(rate.len(), 10);
+    assert_eq!(rate[8..].iter().sum::<u64>(), 3);
+    let counts = log.counts();
+    assert_eq!(counts[Severity::Info as usize], (Severity::Info, 1));
+    assert_eq!(counts[Severity::Error as usize], (Severity::Error, 2));
+
+    // Only shown on terminals tall enough for it
+    let app = App::with_items(vec![]);
+    let mut harness = Harness::new(app, 80, 23).unwrap();
+    harness.render().unwrap();
+    assert!(!harness.contains("Events per"));
+    let app = App::with_items(vec![]).with_event_log(log);
+    let mut harness = Harness::new(app, 80, 24).unwrap();
+    harness.render().unwrap();
+    assert!(harness.contains("Events per 6s, last 5m (peak"));
+    assert!(harness.contains("TRA DEB INF WAR ERR CRI"));
+    harness
+        .keys([key(KeyCode::Char('e')), key(KeyCode::Char('t'))])
+        .unwrap();
+    assert!(!harness.contains("Events per"));
+}
+
+#[test]
+fn frames_are_only_due_after_changes() {
+    let mut harness = harness();
+    assert!(!harness.app.is_dirty());
+    harness
+        .app
+        .push_event(Event::new(Severity::Info, "deployed"));
+    assert!(harness.app.is_dirty());
+    harness.render().unwrap();
+    assert!(!harness.app.is_dirty());
+
+    // Keys wait in the queue, and the frame with them
+    harness.app.handle_key(&key(KeyCode::Char('l')));
+    assert!(!harness.app.is_dirty());
+    harness.app.process_signals().unwrap();
+    assert!(harness.app.is_dirty());
+
+    let config = cotermi::config::Config::parse("max-fps = 10\n").unwrap();
+    assert_eq!(config.max_fps, 10);
+    let err = cotermi::config::Config::parse("max-fps = 0\n")
+        .err()
+        .unwrap();
+    assert!(err.contains("max-fps: must be at least 1"), "{}", err);
+}
@@ -0,0 +1,88 @@
+use cotermi::{
+    cli::{self, parse_duration, Invocation},
+    events::Severity,
+    export::Format,
+};
+use std::{ffi::OsString, path::PathBuf, time::Duration};
+
+fn parse(args: &[&str]) -> Result<Invocation, String> {
+    cli::parse(args.iter().map(OsString::from))
+}
+
+#[test]
+fn options_are_parsed_with_or_without_an_equals_sign() {
+    let Ok(Invocation::Run(args)) = parse(&[
+        "--tick-rate=250ms",
+        "--config",
+        "cotermi.toml",
+        "--data-file=items.json",
+        "--theme",
+        "light",
+        "--tail",
+        "a.log",
+        "--tail=b.log",
+        "--run",
+        "make CFLAGS=-O2",
+    ]) else {
+        panic!("expected a run");
+    };
+    assert_eq!(args.tick_rate, Some(Duration::from_millis(250)));
+    assert_eq!(args.config, Some(PathBuf::from("cotermi.toml")));
+    assert_eq!(args.data_file, Some(PathBuf::from("items.json")));
+    assert_eq!(args.theme.as_deref(), Some("light"));
+    assert_eq!(args.tails, [PathBuf::from("a.log"), PathBuf::from("b.log")]);
+    assert_eq!(args.commands, ["make CFLAGS=-O2"]);
+
+    assert!(matches!(
+        parse(&["--tail", "x", "--help"]),
+        Ok(Invocation::Help)
+    ));
+    assert!(matches!(parse(&["--version"]), Ok(Invocation::Version)));
+}
+
+#[test]
+fn invalid_arguments_are_reported() {
+    for (args, expected) in [
+        (&["--tick-rate", "0"][..], "not a positive number"),
+        (&["--tick-rate", "fast"], "not a positive number"),
+        (&["--theme", "neon"], "unknown theme \"neon\""),
+        (&["--config"], "--config needs a value"),
+        (&["--verbose"], "unexpected argument"),
+        (&["export", "--format", "xml"], "unknown format"),
+        (&["export", "--min-level", "loud"], "unknown severity"),
+    ] {
+        let err = parse(args).unwrap_err();
+        assert!(err.contains(expected), "{:?}: {}", args, err);
+    }
+}
+
+#[test]
+fn tick_rates_take_units() {
+    assert_eq!(parse_duration("75"), Some(Duration::from_millis(75)));
+    assert_eq!(parse_duration("75ms"), Some(Duration::from_millis(75)));
+    assert_eq!(parse_duration("2s"), Some(Duration::from_secs(2)));
+    assert_eq!(parse_duration("0s"), None);
+    assert_eq!(parse_duration("-1"), None);
+}
+
+#[test]
+fn export_takes_its_own_options_and_inputs() {
+    let Ok(Invocation::Export(args)) = parse(&[
+        "export",
+        "--format=csv",
+        "--min-level",
+        "warn",
+        "a.log",
+        "-",
+        "--output",
+        "out.csv",
+        "--config=ci.toml",
+    ]) else {
+        panic!("expected an export");
+    };
+    assert_eq!(args.format, Some(Format::Csv));
+    assert_eq!(args.min_level, Some(Severity::Warning));
+    assert_eq!(args.inputs, [PathBuf::from("a.log"), PathBuf::from("-")]);
+    assert_eq!(args.output, Some(PathBuf::from("out.csv")));
+    assert_eq!(args.config, Some(PathBuf::from("ci.toml")));
+}
@@ -0,0 +1,70 @@
+#![cfg(unix)]
+use cotermi::command::{Command, Output, Stream};
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Polls `command` until it exits, collecting everything it reported.
+fn run_to_end(command: &mut Command) -> Vec<Output> {
+    let deadline = Instant::now() + Duration::from_secs(10);
+    let mut outputs = vec![];
+    while Instant::now() < deadline {
+        outputs.extend(command.poll());
+        if matches!(outputs.last(), Some(Output::Exited(_))) {
+            return outputs;
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+    panic!("{} did not exit in time", command.line());
+}
+
+#[test]
+fn streams_stdout_and_stderr_then_the_exit_status() {
+    let mut command = Command::new("echo out; echo err >&2; exit 3");
+    command.start().unwrap();
+    assert!(command.is_running());
+    let outputs = run_to_end(&mut command);
+    assert!(!command.is_running());
+
+    let lines = outputs
+        .iter()
+        .filter_map(|output| match output {
+            Output::Line(stream, line) => Some((*stream, line.as_str())),
+            Output::Exited(_) => None,
+        })
+        .collect::<Vec<_>>();
+    assert!(lines.contains(&(Stream::Stdout, "out")));
+    assert!(lines.contains(&(Stream::Stderr, "err")));
+    match outputs.last() {
+        Some(Output::Exited(status)) => assert_eq!(status.code(), Some(3)),
+        other => panic!("expected an exit, got {:?}", other),
+    }
+}
+
+#[test]
+fn can_be_killed_and_restarted() {
+    let mut command = Command::new("echo started; sleep 30");
+    command.start().unwrap();
+    command.kill().unwrap();
+    match run_to_end(&mut command).last() {
+        Some(Output::Exited(status)) => assert!(!status.success()),
+        other => panic!("expected an exit, got {:?}", other),
+    }
+
+    command.restart().unwrap();
+    assert!(command.is_running());
+    command.kill().unwrap();
+    run_to_end(&mut command);
+}
+
+#[test]
+fn dropping_stops_what_the_command_left_in_the_background() {
+    let mut command = Command::new("sleep 30 & echo done");
+    command.start().unwrap();
+    run_to_end(&mut command);
+    // The readers are joined, which needs the pipes the sleep holds closed
+    let started = Instant::now();
+    drop(command);
+    assert!(started.elapsed() < Duration::from_secs(10));
+}
@@ -0,0 +1,103 @@
+use cotermi::{
+    config::Config,
+    keymap::KeyChord,
+    utils::{Action, Context},
+};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::time::Duration;
+
+#[test]
+fn config_sets_what_arguments_override() {
+    let mut config = Config::parse("tick-rate = 200\n").unwrap();
+    assert_eq!(config.tick_rate, Duration::from_millis(200));
+    assert!(config.set_theme("high-contrast").is_ok());
+    assert!(config.set_theme("neon").is_err());
+    let err = Config::parse("tick-rate = 0\n").err().unwrap();
+    assert!(err.contains("tick-rate: must be at least 1"), "{}", err);
+}
+
+#[test]
+fn key_chords_take_modifiers_and_key_names() {
+    let parse = |chord: &str| chord.parse::<KeyChord>().unwrap();
+    assert_eq!(
+        parse("ctrl-r"),
+        KeyChord::new(KeyCode::Char('r'), KeyModifiers::CONTROL)
+    );
+    assert_eq!(
+        parse("Alt+Backspace"),
+        KeyChord::new(KeyCode::Backspace, KeyModifiers::ALT)
+    );
+    assert_eq!(
+        parse("ctrl-alt-x"),
+        KeyChord::new(
+            KeyCode::Char('x'),
+            KeyModifiers::CONTROL | KeyModifiers::ALT
+        )
+    );
+    assert_eq!(
+        parse("shift-up"),
+        KeyChord::new(KeyCode::Up, KeyModifiers::SHIFT)
+    );
+    assert_eq!(
+        parse("f5"),
+        KeyChord::new(KeyCode::F(5), KeyModifiers::NONE)
+    );
+    assert_eq!(parse("space").to_string(), "space");
+    assert_eq!(parse("-").code, KeyCode::Char('-'));
+
+    // Shifted letters are the uppercase letter, however they are written
+    // or reported
+    assert_eq!(parse("shift-a"), parse("A"));
+    assert_eq!(parse("A").modifiers, KeyModifiers::NONE);
+    let shifted = KeyEvent::new(KeyCode::Char('A'), KeyModifiers::SHIFT);
+    assert_eq!(KeyChord::from(&shifted), parse("A"));
+    // Likewise shift-tab, which terminals report as backtab
+    let backtab = KeyEvent::new(KeyCode::BackTab, KeyModifiers::SHIFT);
+    assert_eq!(KeyChord::from(&backtab), parse("backtab"));
+    assert_eq!(parse("backtab").to_string(), "backtab");
+
+    for chord in ["hyper-x", "ctrl-", "f25", ""] {
+        let err = chord.parse::<KeyChord>().unwrap_err();
+        assert!(err.starts_with("unknown key"), "{:?}: {}", chord, err);
+    }
+}
+
+#[test]
+fn config_errors_name_every_invalid_entry() {
+    let err = Config::parse(
+        "max-fps = 0\n\
+         [keys.list]\n\
+         x = \"fly\"\n\
+         hyper-k = \"quit\"\n\
+         [keys.sidebar]\n\
+         q = \"quit\"\n\
+         [theme]\n\
+         name = \"neon\"\n\
+         border = \"sparkly\"\n\
+         shadow = \"red\"\n",
+    )
+    .err()
+    .unwrap();
+    assert_eq!(
+        err.split("\n  ").collect::<Vec<_>>(),
+        [
+            "max-fps: must be at least 1",
+            "[keys.list] \"hyper-k\": unknown key \"hyper-k\" in \"hyper-k\"",
+            "[keys.list] \"x\": unknown action \"fly\"",
+            "[keys.sidebar]: unknown context",
+            "[theme] name: unknown theme \"neon\", expected one of dark, light, high-contrast",
+            "[theme] border: unknown color or modifier \"sparkly\"",
+            "[theme] shadow: unknown style \"shadow\"",
+        ]
+    );
+    let err = Config::parse("colour = \"red\"\n").err().unwrap();
+    assert!(err.contains("unknown field `colour`"), "{}", err);
+
+    // Bindings are layered on the defaults, and "none" unbinds a key
+    let config = Config::parse("[keys.list]\nx = \"quit\"\nq = \"none\"\n").unwrap();
+    let key = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE);
+    let action = |c| config.keymap.action(Context::List, &key(c));
+    assert!(matches!(action('x'), Some(Action::Quit)));
+    assert!(action('q').is_none());
+    assert!(matches!(action('Q'), Some(Action::Quit)));
+}
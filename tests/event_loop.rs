@@ -0,0 +1,66 @@
+use cotermi::{
+    event_loop::{Message, Producer, Threads},
+    events::{Event, Severity},
+    harness::Harness,
+    utils::{Action, Context, Signal},
+    App,
+};
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc, Arc,
+    },
+    time::{Duration, Instant},
+};
+
+#[test]
+fn producers_feed_the_app_from_their_own_threads() {
+    let mut app = App::with_items(vec![]);
+    let (sent, wait_sent) = mpsc::channel();
+    let finished = Arc::new(AtomicBool::new(false));
+    let done = finished.clone();
+    app.spawn(move |producer, stop| {
+        producer.event(Event::new(Severity::Warning, "from a thread"));
+        producer.signal(Signal::new(Action::ChangeContext(Context::Events)));
+        sent.send(()).unwrap();
+        while !stop.wait(Duration::from_millis(10)) {}
+        done.store(true, Ordering::SeqCst);
+    });
+    wait_sent.recv().unwrap();
+
+    let mut harness = Harness::new(app, 80, 20).unwrap();
+    harness.tick().unwrap();
+    assert_eq!(harness.app.context(), Context::Events);
+    assert!(harness.contains("WARNING  from a thread"));
+
+    // Dropping the app stops and joins its threads
+    drop(harness);
+    assert!(finished.load(Ordering::SeqCst));
+}
+
+#[test]
+fn ticks_stop_on_shutdown_and_panics_are_reported() {
+    let (sender, receiver) = mpsc::channel();
+    let mut threads = Threads::default();
+    threads.spawn_ticks(Producer::new(sender.clone()), Duration::from_millis(5));
+    assert!(matches!(
+        receiver.recv_timeout(Duration::from_secs(5)),
+        Ok(Message::Tick)
+    ));
+    // A slow tick rate does not hold up the shutdown
+    threads.spawn_ticks(Producer::new(sender.clone()), Duration::from_secs(60));
+    let started = Instant::now();
+    threads.shutdown().unwrap();
+    assert!(started.elapsed() < Duration::from_secs(5));
+
+    // The app is told right away, so it stops rather than wait for input
+    threads.spawn(Producer::new(sender), |_, _| panic!("producer failed"));
+    loop {
+        if let Message::Failed(err) = receiver.recv_timeout(Duration::from_secs(5)).unwrap() {
+            assert!(err.to_string().contains("panicked"), "{}", err);
+            break;
+        }
+    }
+    let err = threads.shutdown().unwrap_err();
+    assert!(err.to_string().contains("1 thread(s)"), "{}", err);
+}
@@ -0,0 +1,104 @@
+use cotermi::{
+    events::{Event, Severity},
+    export::{self, Format},
+    parser::Parser,
+};
+use std::path::Path;
+
+const MAY_FIRST_NOON: u64 = 1_714_564_800_000;
+
+fn event(id: u64, severity: Severity, message: &str) -> Event {
+    let mut event = Event::new(severity, message).with_source("api");
+    event.id = id;
+    event.timestamp = MAY_FIRST_NOON + 250;
+    event
+}
+
+fn written(events: &[Event], format: Format) -> String {
+    let mut out = vec![];
+    export::write(&mut out, events, format).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+#[test]
+fn writes_one_json_object_per_line() {
+    let events = [
+        event(0, Severity::Warning, "slow"),
+        event(1, Severity::Error, "said \"no\""),
+    ];
+    let out = written(&events, Format::JsonLines);
+    let lines = out.lines().collect::<Vec<&str>>();
+    assert_eq!(lines.len(), 2);
+    let value: serde_json::Value = serde_json::from_str(lines[1]).unwrap();
+    assert_eq!(value["id"], 1);
+    assert_eq!(value["timestamp"], "2024-05-01T12:00:00.250Z");
+    assert_eq!(value["severity"], "ERROR");
+    assert_eq!(value["source"], "api");
+    assert_eq!(value["label"], serde_json::Value::Null);
+    assert_eq!(value["status"], "open");
+    assert_eq!(value["message"], "said \"no\"");
+}
+
+#[test]
+fn quotes_csv_fields_only_when_needed() {
+    let events = [
+        event(0, Severity::Info, "plain"),
+        event(1, Severity::Error, "a, \"b\"\nc"),
+    ];
+    assert_eq!(
+        written(&events, Format::Csv),
+        "id,timestamp,severity,source,label,status,message\n\
+         0,2024-05-01T12:00:00.250Z,INFO,api,,open,plain\n\
+         1,2024-05-01T12:00:00.250Z,ERROR,api,,open,\"a, \"\"b\"\"\nc\"\n"
+    );
+}
+
+#[test]
+fn formats_follow_names_and_extensions() {
+    assert_eq!(Format::from_name("NDJSON"), Some(Format::JsonLines));
+    assert_eq!(Format::from_path(Path::new("out.csv")), Some(Format::Csv));
+    assert_eq!(Format::from_path(Path::new("out")), None);
+    assert_eq!(
+        export::file_name(Path::new("exports"), MAY_FIRST_NOON, Format::Csv),
+        Path::new("exports/events-20240501-120000.csv")
+    );
+}
+
+#[test]
+fn exports_within_the_same_second_get_their_own_files() {
+    let dir = std::env::temp_dir().join(format!("cotermi-exports-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let events = [event(0, Severity::Info, "plain")];
+    let paths = (0..3)
+        .map(|_| export::to_new_file(&dir, MAY_FIRST_NOON, &events, Format::JsonLines).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        paths,
+        [
+            dir.join("events-20240501-120000.jsonl"),
+            dir.join("events-20240501-120000-2.jsonl"),
+            dir.join("events-20240501-120000-3.jsonl"),
+        ]
+    );
+    for path in &paths {
+        assert_eq!(std::fs::read_to_string(path).unwrap().lines().count(), 1);
+    }
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn reads_lines_through_the_parser() {
+    let input = "level=debug msg=noise\r\nlevel=error msg=\"db down\"\nWARNING plain\n";
+    let events = export::read(
+        input.as_bytes(),
+        &Parser::default(),
+        Some("app.log"),
+        Severity::Info,
+    )
+    .unwrap();
+    assert_eq!(events.len(), 2);
+    assert_eq!((events[0].id, events[0].severity), (0, Severity::Error));
+    assert_eq!(events[0].message, "db down");
+    assert_eq!(events[0].source.as_deref(), Some("app.log"));
+    assert_eq!((events[1].id, events[1].severity), (1, Severity::Warning));
+}
@@ -0,0 +1,179 @@
+use cotermi::{
+    config::Config,
+    events::Severity,
+    parser::{infer_severity, parse_timestamp, Format, Keys, Parser},
+};
+use regex::Regex;
+
+const MAY_FIRST_NOON: u64 = 1_714_564_800_000;
+
+#[test]
+fn parses_rfc3339_and_unix_timestamps() {
+    assert_eq!(
+        parse_timestamp("2024-05-01T12:00:00Z"),
+        Some(MAY_FIRST_NOON)
+    );
+    assert_eq!(
+        parse_timestamp("2024-05-01 14:00:00.250+02:00"),
+        Some(MAY_FIRST_NOON + 250)
+    );
+    assert_eq!(parse_timestamp("1714564800"), Some(MAY_FIRST_NOON));
+    assert_eq!(parse_timestamp("1714564800000"), Some(MAY_FIRST_NOON));
+    assert_eq!(parse_timestamp("yesterday"), None);
+    assert_eq!(parse_timestamp("2024-05-01T12:00:00+0é00"), None);
+    assert_eq!(parse_timestamp("2024-05-01T12:00:00+é:00"), None);
+}
+
+#[test]
+fn parses_json_lines() {
+    let parser = Parser::new(Format::Json);
+    let event =
+        parser.parse(r#"{"level":"warn","msg":"disk 91% full","time":"2024-05-01T12:00:00Z"}"#);
+    assert_eq!(event.severity, Severity::Warning);
+    assert_eq!(event.message, "disk 91% full");
+    assert_eq!(event.timestamp, MAY_FIRST_NOON);
+
+    let event = parser.parse(r#"{"level":50,"msg":"crashed"}"#);
+    assert_eq!(event.severity, Severity::Error);
+}
+
+#[test]
+fn json_keys_are_configurable() {
+    let keys = Keys {
+        level: vec!["sev".to_string()],
+        message: vec!["text".to_string()],
+        timestamp: vec!["at".to_string()],
+    };
+    let parser = Parser::new(Format::Json).with_keys(keys);
+    let event = parser.parse(r#"{"sev":"critical","text":"down","at":1714564800}"#);
+    assert_eq!(event.severity, Severity::Critical);
+    assert_eq!(event.message, "down");
+    assert_eq!(event.timestamp, MAY_FIRST_NOON);
+}
+
+#[test]
+fn parses_logfmt() {
+    let parser = Parser::new(Format::Logfmt);
+    let event =
+        parser.parse(r#"ts=2024-05-01T12:00:00Z level=error msg="upstream \"db\" gone" retry"#);
+    assert_eq!(event.severity, Severity::Error);
+    assert_eq!(event.message, r#"upstream "db" gone"#);
+    assert_eq!(event.timestamp, MAY_FIRST_NOON);
+}
+
+#[test]
+fn parses_rfc5424_syslog() {
+    let parser = Parser::new(Format::Syslog);
+    let event = parser.parse(
+        r#"<34>1 2024-05-01T12:00:00Z host su - ID47 [exampleSDID@32473 iut="3" eventSource="App"] 'su root' failed"#,
+    );
+    assert_eq!(event.severity, Severity::Critical);
+    assert_eq!(event.message, "su: 'su root' failed");
+    assert_eq!(event.timestamp, MAY_FIRST_NOON);
+
+    let event = parser.parse("<12>1 - - - - - - low memory");
+    assert_eq!(event.severity, Severity::Warning);
+    assert_eq!(event.message, "low memory");
+}
+
+#[test]
+fn parses_with_named_captures() {
+    let pattern = Regex::new(r"^\[(?P<level>\w+)\] (?P<message>.*)$").unwrap();
+    let parser = Parser::new(Format::Regex).with_pattern(pattern);
+    let event = parser.parse("[debug] cache warmed");
+    assert_eq!(event.severity, Severity::Debug);
+    assert_eq!(event.message, "cache warmed");
+}
+
+#[test]
+fn unparsable_lines_are_kept_with_an_inferred_severity() {
+    let parser = Parser::new(Format::Json);
+    let event = parser.parse("Traceback: FATAL error in worker");
+    assert_eq!(event.severity, Severity::Critical);
+    assert_eq!(event.message, "Traceback: FATAL error in worker");
+
+    let event = Parser::default().parse(r#"{"ts":"2024-05-01T12:00:00+0é00","msg":"x"}"#);
+    assert_eq!(event.message, "x");
+    let event = Parser::default().parse("ts=2024-05-01T12:00:00+0é00 msg=y");
+    assert_eq!(event.message, "y");
+
+    let event = Parser::default().parse("nothing to see here");
+    assert_eq!(event.severity, Severity::Info);
+}
+
+#[test]
+fn only_words_shaped_like_levels_set_the_severity() {
+    for (line, severity) in [
+        ("warn: disk almost full", Some(Severity::Warning)),
+        ("error[E0308]: mismatched types", Some(Severity::Error)),
+        (
+            "2024-05-01 12:00:00 debug cache warmed",
+            Some(Severity::Debug),
+        ),
+        ("worker 3 [err] lost connection", Some(Severity::Error)),
+        ("thread 'main' panicked: PANIC", Some(Severity::Critical)),
+        ("panic: runtime error", Some(Severity::Critical)),
+        ("don't panic, all good", None),
+        ("Setting alert threshold to 5", None),
+        ("notice the error_rate metric", None),
+        ("test rules::error_path ... ok", None),
+        ("test result: ok. 3 passed; 0 failed", None),
+    ] {
+        assert_eq!(infer_severity(line), severity, "{:?}", line);
+    }
+}
+
+#[test]
+fn auto_detects_the_format_per_line() {
+    let parser = Parser::default();
+    assert_eq!(
+        parser.parse(r#"{"level":"debug","message":"a"}"#).message,
+        "a"
+    );
+    assert_eq!(parser.parse("level=warn msg=b").severity, Severity::Warning);
+    assert_eq!(parser.parse("<11>1 - - app - - - c").message, "app: c");
+    assert_eq!(
+        parser.parse("key=value [ERROR] d").severity,
+        Severity::Error
+    );
+}
+
+#[test]
+fn other_keys_become_fields() {
+    let parser = Parser::default();
+    let event = parser.parse(r#"{"level":"warn","msg":"slow","ms":812,"route":"/a"}"#);
+    assert_eq!(event.message, "slow");
+    assert_eq!(
+        event.fields,
+        [
+            ("ms".to_string(), "812".to_string()),
+            ("route".to_string(), "/a".to_string())
+        ]
+    );
+    let event = parser.parse("msg=slow route=/b level=warn ms=90");
+    assert_eq!(
+        event.fields,
+        [
+            ("route".to_string(), "/b".to_string()),
+            ("ms".to_string(), "90".to_string())
+        ]
+    );
+    assert!(parser.parse("plain line").fields.is_empty());
+}
+
+#[test]
+fn parser_is_configured_in_the_config_file() {
+    let config = Config::parse("[parser]\nformat = \"json\"\nlevel-key = \"sev\"\n").unwrap();
+    assert_eq!(config.parser.format(), Format::Json);
+    assert_eq!(
+        config.parser.parse(r#"{"sev":"error"}"#).severity,
+        Severity::Error
+    );
+
+    let err = Config::parse("[parser]\nformat = \"regex\"\n")
+        .err()
+        .unwrap();
+    assert!(err.contains("needs a pattern"), "{}", err);
+    let err = Config::parse("[parser]\npattern = \"(\"\n").err().unwrap();
+    assert!(err.contains("invalid regex"), "{}", err);
+}
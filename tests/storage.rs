@@ -0,0 +1,67 @@
+use cotermi::{
+    item::Item,
+    storage::{Storage, FORMAT_VERSION},
+};
+use std::{fs, path::PathBuf};
+
+/// A path of its own for each test, in a directory that does not exist yet.
+fn path(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("cotermi-storage-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    dir.join("items.json")
+}
+
+#[test]
+fn saved_items_load_back_unchanged() {
+    let path = path("round-trip");
+    let storage = Storage::new(&path);
+    assert_eq!(storage.load().unwrap(), []);
+
+    let mut items = vec![
+        Item::new(0, "Alpha", "first").with_body(vec!["one".into(), "two".into()]),
+        Item::new(7, "Beta \"quoted\"", ""),
+    ];
+    items[1].created = 1_714_564_800;
+    storage.save(&items).unwrap();
+    assert_eq!(storage.load().unwrap(), items);
+    let contents = fs::read_to_string(&path).unwrap();
+    assert!(contents.contains(&format!("\"version\": {}", FORMAT_VERSION)));
+    // Nothing is left behind from writing it
+    assert!(!path.with_extension("json.tmp").exists());
+
+    items.remove(0);
+    storage.save(&items).unwrap();
+    assert_eq!(storage.load().unwrap(), items);
+    fs::remove_dir_all(path.parent().unwrap()).unwrap();
+}
+
+#[test]
+fn version_1_files_are_migrated() {
+    let path = path("v1");
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(
+        &path,
+        r#"{"version": 1, "items": [{"title": "Alpha", "lines": 2}, {"title": "Beta", "lines": 0}]}"#,
+    )
+    .unwrap();
+    let storage = Storage::new(&path);
+    let items = storage.load().unwrap();
+    let ids = items.iter().map(|item| item.id).collect::<Vec<_>>();
+    assert_eq!(ids, [0, 1]);
+    assert_eq!((items[0].title.as_str(), items[0].body.len()), ("Alpha", 2));
+    assert_eq!((items[1].title.as_str(), items[1].body.len()), ("Beta", 0));
+    assert!(items.iter().all(|item| item.description.is_empty()));
+
+    // Saving writes the current version, which loads as is
+    storage.save(&items).unwrap();
+    assert_eq!(storage.load().unwrap(), items);
+
+    fs::write(&path, r#"{"version": 99, "items": []}"#).unwrap();
+    let err = storage.load().unwrap_err();
+    assert!(
+        err.to_string().contains("unsupported format version 99"),
+        "{}",
+        err
+    );
+    fs::remove_dir_all(path.parent().unwrap()).unwrap();
+}
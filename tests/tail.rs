@@ -0,0 +1,77 @@
+use cotermi::tail::Tail;
+use std::{
+    env, fs,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = env::temp_dir().join(format!("cotermi-tail-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn append(path: &Path, text: &str) {
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .unwrap();
+    file.write_all(text.as_bytes()).unwrap();
+}
+
+#[test]
+fn follows_appended_lines_from_the_end() {
+    let dir = scratch_dir("append");
+    let path = dir.join("app.log");
+    append(&path, "old line\n");
+    let mut tail = Tail::new(&path);
+    assert!(tail.poll().unwrap().is_empty());
+
+    append(&path, "first\nsecond\r\nthi");
+    assert_eq!(tail.poll().unwrap(), ["first", "second"]);
+    append(&path, "rd\n");
+    assert_eq!(tail.poll().unwrap(), ["third"]);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn waits_for_missing_files_and_reads_them_from_the_start() {
+    let dir = scratch_dir("missing");
+    let path = dir.join("later.log");
+    let mut tail = Tail::new(&path);
+    assert!(tail.poll().unwrap().is_empty());
+    append(&path, "hello\n");
+    assert_eq!(tail.poll().unwrap(), ["hello"]);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn rereads_truncated_files() {
+    let dir = scratch_dir("truncate");
+    let path = dir.join("app.log");
+    append(&path, "a long line before truncation\n");
+    let mut tail = Tail::new(&path);
+    fs::write(&path, "").unwrap();
+    assert!(tail.poll().unwrap().is_empty());
+    append(&path, "fresh\n");
+    assert_eq!(tail.poll().unwrap(), ["fresh"]);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn follows_rotated_files() {
+    let dir = scratch_dir("rotate");
+    let path = dir.join("app.log");
+    append(&path, "");
+    let mut tail = Tail::new(&path);
+    append(&path, "before\nunterminated");
+    fs::rename(&path, dir.join("app.log.1")).unwrap();
+    assert_eq!(tail.poll().unwrap(), ["before"]);
+
+    append(&path, "after\n");
+    assert_eq!(tail.poll().unwrap(), ["unterminated", "after"]);
+    fs::remove_dir_all(dir).unwrap();
+}
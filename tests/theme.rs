@@ -0,0 +1,57 @@
+use cotermi::{
+    config::Config,
+    theme::{parse_style, Theme},
+};
+use tui::style::{Color, Modifier, Style};
+
+#[test]
+fn styles_take_colors_and_modifiers_in_any_order() {
+    assert_eq!(parse_style("red").unwrap(), Style::default().fg(Color::Red));
+    assert_eq!(
+        parse_style("bold White on #ff8800 italic").unwrap(),
+        Style::default()
+            .fg(Color::White)
+            .bg(Color::Rgb(0xff, 0x88, 0x00))
+            .add_modifier(Modifier::BOLD | Modifier::ITALIC)
+    );
+    assert_eq!(
+        parse_style("244 reverse").unwrap(),
+        Style::default()
+            .fg(Color::Indexed(244))
+            .add_modifier(Modifier::REVERSED)
+    );
+    assert_eq!(parse_style("").unwrap(), Style::default());
+    for (spec, expected) in [
+        ("red on", "missing background color after \"on\""),
+        ("#ff88zz", "invalid color \"#ff88zz\""),
+        ("#aébcd", "invalid color \"#aébcd\""),
+        ("sparkly", "unknown color or modifier \"sparkly\""),
+    ] {
+        let err = parse_style(spec).unwrap_err();
+        assert!(err.contains(expected), "{:?}: {}", spec, err);
+    }
+}
+
+#[test]
+fn no_color_strips_colors_but_keeps_styles_apart() {
+    let stripped = Theme::dark().without_colors();
+    for style in [stripped.border, stripped.highlight, stripped.severity.error] {
+        assert_eq!((style.fg, style.bg), (None, None));
+    }
+    assert!(stripped.highlight.add_modifier.contains(Modifier::REVERSED));
+    assert_ne!(stripped.severity.info, stripped.severity.error);
+    assert_ne!(stripped.severity.error, stripped.severity.critical);
+
+    assert_eq!(Theme::dark().apply_no_color(true), stripped);
+    assert_eq!(Theme::dark().apply_no_color(false), Theme::dark());
+
+    // Style overrides are stripped too
+    let contents = "[theme]\nborder = \"red bold\"\n";
+    let config = Config::parse_with(contents, true).unwrap();
+    assert_eq!(
+        config.theme.border,
+        Style::default().add_modifier(Modifier::BOLD)
+    );
+    let config = Config::parse_with(contents, false).unwrap();
+    assert_eq!(config.theme.border.fg, Some(Color::Red));
+}
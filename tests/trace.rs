@@ -0,0 +1,32 @@
+use cotermi::{events::Severity, harness::Harness, App};
+use tracing_subscriber::layer::SubscriberExt;
+
+#[test]
+fn tracing_events_are_logged_with_their_span_fields() {
+    let app = App::with_items(vec![]);
+    let subscriber = tracing_subscriber::registry().with(app.tracing_layer());
+    tracing::subscriber::with_default(subscriber, || {
+        let span = tracing::info_span!("request", id = 7, user = tracing::field::Empty);
+        span.record("user", "ann");
+        span.in_scope(|| tracing::warn!(target: "api::db", rows = 3, "slow {}", "query"));
+        tracing::error!("unscoped");
+    });
+    let mut harness = Harness::new(app, 100, 20).unwrap();
+    assert!(harness.app.events().is_empty());
+    harness.tick().unwrap();
+
+    let events = harness.app.events().iter().collect::<Vec<_>>();
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0].severity, Severity::Warning);
+    assert_eq!(events[0].source.as_deref(), Some("api::db"));
+    assert_eq!(events[0].message, "request: slow query");
+    let fields = [("id", "7"), ("user", "ann"), ("rows", "3")]
+        .map(|(name, value)| (name.to_string(), value.to_string()));
+    assert_eq!(events[0].fields, fields);
+    assert_eq!(events[1].severity, Severity::Error);
+    assert_eq!(events[1].message, "unscoped");
+    assert!(events[1].fields.is_empty());
+
+    harness.render().unwrap();
+    assert!(harness.contains("request: slow query id=7 user=ann rows=3"));
+}